@@ -0,0 +1,72 @@
+//! Times lexing and parsing a large generated script. Run with `cargo bench`.
+//!
+//! The `owned` row lexes the same script but gives every token its own copy of its literal, as
+//! the lexer did before tokens borrowed from the source. It is the baseline for `lex`. The
+//! figures quoted for that change come from running this bench on the commit before it.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use monkers::lexer::Lexer;
+use monkers::parser::Parser;
+
+const STATEMENTS: usize = 20_000;
+const RUNS: usize = 15;
+
+/// A script mixing the constructs a generated program tends to repeat: bindings, functions,
+/// calls, conditionals, strings and comments.
+fn script() -> String {
+    let mut source = String::new();
+    for i in 0..STATEMENTS {
+        source.push_str(&format!(
+            "/// Binding number {i}.\n\
+             let value_{i} = fn(x, y) {{ if (x < y) {{ x * {i} + y }} else {{ \"item {i}\\n\" + \"done\" }} }};\n\
+             value_{i}(1_000, 0x{i:x}) == 2.5e3 && !false; // trailing comment\n"
+        ));
+    }
+    source
+}
+
+/// Runs `f` `RUNS` times and returns the median duration.
+fn median(mut f: impl FnMut()) -> Duration {
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times[RUNS / 2]
+}
+
+fn report(name: &str, bytes: usize, time: Duration) {
+    let throughput = bytes as f64 / time.as_secs_f64() / (1024.0 * 1024.0);
+    println!("{:<6} {:>10.2?} {:>8.1} MiB/s", name, time, throughput);
+}
+
+fn main() {
+    let source = script();
+    println!("{} statements, {} bytes, median of {} runs", STATEMENTS * 2, source.len(), RUNS);
+
+    let lex = median(|| {
+        let lexer = Lexer::new(black_box(&source));
+        black_box(lexer.count());
+    });
+    report("lex", source.len(), lex);
+
+    let owned = median(|| {
+        for token in Lexer::new(black_box(&source)) {
+            black_box(token.into_owned());
+        }
+    });
+    report("owned", source.len(), owned);
+
+    let parse = median(|| {
+        let mut parser = Parser::new(Lexer::new(black_box(&source)));
+        let (program, errors) = parser.parse_program();
+        assert!(errors.is_empty());
+        black_box(program);
+    });
+    report("parse", source.len(), parse);
+}
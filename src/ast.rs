@@ -1,14 +1,24 @@
-use crate::ast::show::Show;
-use crate::lexer::token::Token;
+use crate::ast::show::{quote, Show};
+use crate::lexer::token::Span;
 pub mod show;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Statement {
     Let(LetStatement),
     Return(ReturnStatement),
     Expression(ExpressionStatement),
 }
 
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let(let_statement) => let_statement.span,
+            Statement::Return(return_statement) => return_statement.span,
+            Statement::Expression(expression_statement) => expression_statement.span,
+        }
+    }
+}
+
 impl Show for Statement {
     fn show(&self) -> String {
         match self {
@@ -19,33 +29,148 @@ impl Show for Statement {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expression {
     Id(Identifier),
     Lit(Literal),
     Integer(IntegerLiteral),
+    Float(FloatLiteral),
+    Boolean(BooleanLiteral),
+    StringLiteral(StringLiteral),
     Prefix {
         operator: String,
         right: Box<Expression>,
+        span: Span,
+    },
+    Infix {
+        left: Box<Expression>,
+        operator: String,
+        right: Box<Expression>,
+        span: Span,
+    },
+    If {
+        condition: Box<Expression>,
+        consequence: BlockStatement,
+        alternative: Option<BlockStatement>,
+        span: Span,
+    },
+    FunctionLiteral {
+        parameters: Vec<Identifier>,
+        body: BlockStatement,
+        span: Span,
+    },
+    Call {
+        function: Box<Expression>,
+        arguments: Vec<Expression>,
+        span: Span,
+    },
+    Array {
+        elements: Vec<Expression>,
+        span: Span,
+    },
+    Index {
+        left: Box<Expression>,
+        index: Box<Expression>,
+        span: Span,
     },
+    /// A `{key: value}` literal, with its pairs in source order.
+    Hash {
+        pairs: Vec<(Expression, Expression)>,
+        span: Span,
+    },
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Id(id) => id.span,
+            Expression::Lit(lit) => lit.span,
+            Expression::Integer(int) => int.span,
+            Expression::Float(float) => float.span,
+            Expression::Boolean(boolean) => boolean.span,
+            Expression::StringLiteral(string) => string.span,
+            Expression::Prefix { span, .. } => *span,
+            Expression::Infix { span, .. } => *span,
+            Expression::If { span, .. } => *span,
+            Expression::FunctionLiteral { span, .. } => *span,
+            Expression::Call { span, .. } => *span,
+            Expression::Array { span, .. } => *span,
+            Expression::Index { span, .. } => *span,
+            Expression::Hash { span, .. } => *span,
+        }
+    }
+
+    pub fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Expression::Id(id) => &mut id.span,
+            Expression::Lit(lit) => &mut lit.span,
+            Expression::Integer(int) => &mut int.span,
+            Expression::Float(float) => &mut float.span,
+            Expression::Boolean(boolean) => &mut boolean.span,
+            Expression::StringLiteral(string) => &mut string.span,
+            Expression::Prefix { span, .. } => span,
+            Expression::Infix { span, .. } => span,
+            Expression::If { span, .. } => span,
+            Expression::FunctionLiteral { span, .. } => span,
+            Expression::Call { span, .. } => span,
+            Expression::Array { span, .. } => span,
+            Expression::Index { span, .. } => span,
+            Expression::Hash { span, .. } => span,
+        }
+    }
 }
 
 impl Show for Expression {
     fn show(&self) -> String {
         match self {
-            Expression::Id(id) => id.0.clone(),
-            Expression::Lit(lit) => lit.0.clone(),
-            Expression::Integer(int) => int.0.to_string(),
-            Expression::Prefix { operator, right } => format!("({}{})", operator, right.show()),
+            Expression::Id(id) => id.name.clone(),
+            Expression::Lit(lit) => lit.value.clone(),
+            Expression::Integer(int) => int.value.to_string(),
+            // `{:?}` keeps the `.0` on whole numbers so they still read back as floats.
+            Expression::Float(float) => format!("{:?}", float.value),
+            Expression::Boolean(boolean) => boolean.value.to_string(),
+            Expression::StringLiteral(string) => quote(&string.value),
+            Expression::Prefix { operator, right, .. } => format!("({}{})", operator, right.show()),
+            Expression::Infix { left, operator, right, .. } => format!("({} {} {})", left.show(), operator, right.show()),
+            Expression::If { condition, consequence, alternative, .. } => {
+                // Prefix, infix and index expressions already show their own parentheses.
+                let condition = match condition.as_ref() {
+                    Expression::Prefix { .. } | Expression::Infix { .. } | Expression::Index { .. } => condition.show(),
+                    _ => format!("({})", condition.show()),
+                };
+                match alternative {
+                    Some(alternative) => format!("if {} {} else {}", condition, consequence.show(), alternative.show()),
+                    None => format!("if {} {}", condition, consequence.show()),
+                }
+            }
+            Expression::FunctionLiteral { parameters, body, .. } => {
+                let parameters: Vec<&str> = parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+                format!("fn({}) {}", parameters.join(", "), body.show())
+            }
+            Expression::Call { function, arguments, .. } => {
+                let arguments: Vec<String> = arguments.iter().map(|argument| argument.show()).collect();
+                format!("{}({})", function.show(), arguments.join(", "))
+            }
+            Expression::Array { elements, .. } => {
+                let elements: Vec<String> = elements.iter().map(|element| element.show()).collect();
+                format!("[{}]", elements.join(", "))
+            }
+            Expression::Index { left, index, .. } => format!("({}[{}])", left.show(), index.show()),
+            Expression::Hash { pairs, .. } => {
+                let pairs: Vec<String> = pairs.iter().map(|(key, value)| format!("{}: {}", key.show(), value.show())).collect();
+                format!("{{{}}}", pairs.join(", "))
+            }
         }
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
 pub struct Program {
     pub statements: Vec<Statement>,
 }
 
 impl Program {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Self {
             statements: Vec::new(),
@@ -55,68 +180,129 @@ impl Program {
 
 impl Show for Program {
     fn show(&self) -> String {
-        let mut program = String::new();
-        for statement in &self.statements {
-            program.push_str(&statement.show());
-        }
-        program
+        show_statements(&self.statements).concat()
     }
 }
 
+/// Shows each statement in turn. An expression statement followed by another needs its `;`,
+/// or `x; -y` would show as `x(-y)`, which reads back as a call.
+fn show_statements(statements: &[Statement]) -> Vec<String> {
+    let last = statements.len().saturating_sub(1);
+    statements
+        .iter()
+        .enumerate()
+        .map(|(i, statement)| match statement {
+            Statement::Expression(expression_statement) if i < last => format!("{};", expression_statement.show()),
+            statement => statement.show(),
+        })
+        .collect()
+}
+
 #[derive(Debug, PartialEq, Clone)]
-pub struct Identifier(pub String);
+pub struct Identifier {
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Literal {
+    pub value: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct IntegerLiteral {
+    pub value: i64,
+    pub span: Span,
+}
 
-#[derive(Debug, PartialEq)]
-pub struct Literal(pub String);
+#[derive(Debug, PartialEq, Clone)]
+pub struct FloatLiteral {
+    pub value: f64,
+    pub span: Span,
+}
 
-#[derive(Debug, PartialEq)]
-pub struct IntegerLiteral(pub i64);
+#[derive(Debug, PartialEq, Clone)]
+pub struct BooleanLiteral {
+    pub value: bool,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StringLiteral {
+    pub value: String,
+    pub span: Span,
+}
 
 pub struct PrefixExpression {
     pub operator: String,
     pub right: Box<Expression>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct LetStatement {
-    pub token: Token,
     pub name: Identifier,
     pub value: Expression,
+    /// The `///` doc comment lines directly above the statement, joined with newlines.
+    pub doc: Option<String>,
+    pub span: Span,
 }
 
 impl Show for LetStatement {
     fn show(&self) -> String {
-        format!("{} {} = {};", self.token.literal, self.name.0, self.value.show())
+        let mut shown = String::new();
+        if let Some(doc) = &self.doc {
+            for line in doc.split('\n') {
+                shown.push_str(&format!("/// {}\n", line));
+            }
+        }
+        shown.push_str(&format!("let {} = {};", self.name.name, self.value.show()));
+        shown
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ReturnStatement {
-    pub token: Token,
     pub return_value: Expression,
+    pub span: Span,
 }
 
 impl Show for ReturnStatement {
     fn show(&self) -> String {
-        format!("{} {};", self.token.literal, self.return_value.show())
+        format!("return {};", self.return_value.show())
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct ExpressionStatement {
-    pub token: Token,
     pub expression: Expression,
+    pub span: Span,
 }
 
 impl Show for ExpressionStatement {
     fn show(&self) -> String {
-        format!("{}", self.expression.show())
+        self.expression.show()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlockStatement {
+    pub statements: Vec<Statement>,
+    pub span: Span,
+}
+
+impl Show for BlockStatement {
+    fn show(&self) -> String {
+        if self.statements.is_empty() {
+            return "{ }".to_string();
+        }
+        let statements = show_statements(&self.statements);
+        format!("{{ {} }}", statements.join(" "))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::lexer::token::TokenType::{Let, Return};
     use super::*;
 
     #[test]
@@ -124,16 +310,17 @@ mod tests {
         let program = Program {
             statements: vec![
                 Statement::Let(LetStatement {
-                    token: Token { token_type: Let, literal: "let".to_string() },
-                    name: Identifier("myVar".to_string()),
-                    value: Expression::Id(Identifier("anotherVar".to_string())),
+                    name: Identifier { name: "myVar".to_string(), span: Span::default() },
+                    value: Expression::Id(Identifier { name: "anotherVar".to_string(), span: Span::default() }),
+                    doc: None,
+                    span: Span::default(),
                 }),
                 Statement::Return(ReturnStatement {
-                    token: Token { token_type: Return, literal: "return".to_string() },
-                    return_value: Expression::Lit(Literal("5".to_string())),
+                    return_value: Expression::Lit(Literal { value: "5".to_string(), span: Span::default() }),
+                    span: Span::default(),
                 }),
             ],
         };
         assert_eq!(program.show(), "let myVar = anotherVar;return 5;");
     }
-}
\ No newline at end of file
+}
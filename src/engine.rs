@@ -0,0 +1,283 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::io::Write;
+use std::rc::Rc;
+
+use crate::ast::Program;
+use crate::engine::convert::{ConversionError, FromObject, IntoObject};
+use crate::engine::native::IntoNativeFn;
+use crate::eval::environment::Environment;
+use crate::eval::eval_program;
+use crate::eval::object::{NativeFunction, Object};
+use crate::lexer::Lexer;
+use crate::parser::error::ParseError;
+use crate::parser::Parser;
+
+pub mod convert;
+pub mod native;
+
+/// Runs Monkey code on behalf of a host program. Globals, including registered native
+/// functions, persist across evaluations, as in the REPL.
+///
+/// Deeply recursive scripts need a big Rust stack, so hosts should evaluate on a thread with at
+/// least `eval::STACK_SIZE` of stack, or lower the limit with `set_max_call_depth`.
+#[derive(Default)]
+pub struct Engine {
+    env: Environment,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum EngineError {
+    /// The source did not parse; holds every error found.
+    Parse(Vec<ParseError>),
+    /// Evaluation produced a Monkey error object; holds its message.
+    Runtime(String),
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an engine whose scripts print, such as with `puts`, to `output` rather than
+    /// standard output.
+    pub fn with_output(output: impl Write + 'static) -> Self {
+        Self {
+            env: Environment::with_output(output),
+        }
+    }
+
+    /// Parses and evaluates `source`, returning the value of its last statement.
+    pub fn eval_str(&mut self, source: &str) -> Result<Object, EngineError> {
+        let mut parser = Parser::new(Lexer::new(source));
+        let (program, errors) = parser.parse_program();
+        if !errors.is_empty() {
+            return Err(EngineError::Parse(errors));
+        }
+        self.eval_program(&program)
+    }
+
+    pub fn eval_program(&mut self, program: &Program) -> Result<Object, EngineError> {
+        match eval_program(program, &mut self.env) {
+            Object::Error(message) => Err(EngineError::Runtime(message)),
+            result => Ok(result),
+        }
+    }
+
+    pub fn set_global(&mut self, name: &str, value: impl IntoObject) {
+        self.env.set(name, value.into_object());
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Object> {
+        self.env.get(name)
+    }
+
+    /// Looks up a global and converts it to `T`, as in `engine.get_global_as::<Vec<i64>>("xs")`.
+    /// Returns `None` if the global is unbound and an error if it has the wrong type.
+    pub fn get_global_as<T: FromObject>(&self, name: &str) -> Option<Result<T, ConversionError>> {
+        self.env.get(name).map(|value| T::from_object(&value))
+    }
+
+    /// Sets how deeply function calls may nest before a script fails with "maximum call depth
+    /// exceeded". The default is `eval::DEFAULT_MAX_CALL_DEPTH`.
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.env.set_max_call_depth(depth);
+    }
+
+    /// Binds `name` to a Monkey function that calls `function`. This can take the raw
+    /// `&[Object]` arguments, or typed parameters such as `fn(i64, String) -> Vec<i64>` that are
+    /// converted with `FromObject`, in which case arguments that do not convert raise an error
+    /// naming the argument. The result is converted with `IntoObject`, and an `Err` result is
+    /// raised in the script as an error object carrying the error's message.
+    pub fn register_fn<Args>(&mut self, name: &str, function: impl IntoNativeFn<Args>) {
+        let native = NativeFunction {
+            name: name.to_string(),
+            function: function.into_native_fn(name),
+        };
+        self.env.set(name, Object::Native(Rc::new(native)));
+    }
+}
+
+// Global functions hold handles to the global scope, which would otherwise keep it, and every
+// registered closure, alive after the engine is gone.
+impl Drop for Engine {
+    fn drop(&mut self) {
+        self.env.clear();
+    }
+}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EngineError::Parse(errors) => {
+                let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+                write!(f, "{}", messages.join("\n"))
+            }
+            EngineError::Runtime(message) => write!(f, "runtime error: {}", message),
+        }
+    }
+}
+
+impl Error for EngineError {}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use crate::ast::show::Show;
+    use crate::engine::{Engine, EngineError};
+    use crate::eval::environment::OutputBuffer;
+    use crate::eval::object::Object;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_eval_str_keeps_globals() {
+        let mut engine = Engine::new();
+        assert_eq!(engine.eval_str("let x = 2;"), Ok(Object::Null));
+        assert_eq!(engine.eval_str("x * 21"), Ok(Object::Integer(42)));
+        assert_eq!(engine.get_global("x"), Some(Object::Integer(2)));
+        assert_eq!(engine.get_global("y"), None);
+    }
+
+    #[test]
+    fn test_eval_program() {
+        let mut parser = Parser::new(Lexer::new("let greeting = \"hi \" + name; greeting"));
+        let (program, errors) = parser.parse_program();
+        assert!(errors.is_empty());
+        let mut engine = Engine::new();
+        engine.set_global("name", Object::String("Jo".to_string()));
+        assert_eq!(engine.eval_program(&program), Ok(Object::String("hi Jo".to_string())));
+        assert_eq!(program.show(), "let greeting = (\"hi \" + name);greeting");
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut engine = Engine::new();
+        match engine.eval_str("let = 1; let y 2;") {
+            Err(EngineError::Parse(errors)) => assert_eq!(errors.len(), 2),
+            result => panic!("Expected parse errors, got {:?}", result),
+        }
+        let error = engine.eval_str("1 + true").unwrap_err();
+        assert_eq!(error, EngineError::Runtime("type mismatch: INTEGER + BOOLEAN".to_string()));
+        assert_eq!(error.to_string(), "runtime error: type mismatch: INTEGER + BOOLEAN");
+    }
+
+    #[test]
+    fn test_register_fn() {
+        let mut engine = Engine::new();
+        engine.register_fn("add", |args: &[Object]| match args {
+            [Object::Integer(a), Object::Integer(b)] => Ok(Object::Integer(a + b)),
+            _ => Err("add expects two integers"),
+        });
+        assert_eq!(engine.eval_str("add(1, 2)"), Ok(Object::Integer(3)));
+        assert_eq!(engine.eval_str("let twice = fn(f, x) { f(f(x, x), x) }; twice(add, 5)"), Ok(Object::Integer(15)));
+        assert_eq!(engine.eval_str("type(add)"), Ok(Object::String("BUILTIN".to_string())));
+        assert_eq!(engine.eval_str("add(1)"), Err(EngineError::Runtime("add expects two integers".to_string())));
+    }
+
+    #[test]
+    fn test_register_typed_fn() {
+        fn range(start: i64, end: i64) -> Vec<i64> {
+            (start..end).collect()
+        }
+        let mut engine = Engine::new();
+        engine.register_fn("range", range);
+        engine.register_fn("repeat", |n: i64, s: String| s.repeat(n as usize));
+        engine.register_fn("sum", |xs: Vec<i64>| xs.iter().sum::<i64>());
+        engine.register_fn("lookup", |table: HashMap<String, i64>, key: String| table.get(&key).copied());
+        engine.register_fn("answer", || 42i64);
+        engine.register_fn("checked_div", |a: i64, b: i64| a.checked_div(b).ok_or("division by zero"));
+        let tests = [
+            ("range(1, 4)", Object::Array(vec![Object::Integer(1), Object::Integer(2), Object::Integer(3)])),
+            (r#"repeat(3, "ab")"#, Object::String("ababab".to_string())),
+            ("sum(range(0, 5))", Object::Integer(10)),
+            (r#"lookup({"a": 1}, "a")"#, Object::Integer(1)),
+            (r#"lookup({"a": 1}, "b")"#, Object::Null),
+            ("answer()", Object::Integer(42)),
+            ("checked_div(7, 2)", Object::Integer(3)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(engine.eval_str(input), Ok(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_typed_fn_argument_errors() {
+        let mut engine = Engine::new();
+        engine.register_fn("repeat", |n: i64, s: String| s.repeat(n as usize));
+        engine.register_fn("sum", |xs: Vec<i64>| xs.iter().sum::<i64>());
+        engine.register_fn("checked_div", |a: i64, b: i64| a.checked_div(b).ok_or("division by zero"));
+        let tests = [
+            (r#"repeat("ab", 3)"#, "argument 1 to `repeat`: expected INTEGER, got STRING"),
+            ("repeat(3, 3)", "argument 2 to `repeat`: expected STRING, got INTEGER"),
+            ("repeat(3)", "wrong number of arguments: expected 2, got 1"),
+            (r#"sum([1, "2"])"#, "argument 1 to `sum`: expected INTEGER at [1], got STRING"),
+            ("sum(1)", "argument 1 to `sum`: expected ARRAY of INTEGER, got INTEGER"),
+            ("checked_div(1, 0)", "division by zero"),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(engine.eval_str(input), Err(EngineError::Runtime(expected.to_string())), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_typed_globals() {
+        let mut engine = Engine::new();
+        engine.set_global("xs", vec![1i64, 2, 3]);
+        engine.set_global("name", "Jo");
+        assert_eq!(engine.eval_str("len(xs) + len(name)"), Ok(Object::Integer(5)));
+        engine.eval_str("let pair = [first(xs), name];").unwrap();
+        assert_eq!(engine.get_global_as::<(i64, String)>("pair"), Some(Ok((1, "Jo".to_string()))));
+        assert!(matches!(engine.get_global_as::<bool>("xs"), Some(Err(_))));
+        assert_eq!(engine.get_global_as::<bool>("missing"), None);
+    }
+
+    #[test]
+    fn test_set_max_call_depth() {
+        let mut engine = Engine::new();
+        engine.eval_str("let count = fn(n) { if (n == 0) { 0 } else { 1 + count(n - 1) } };").unwrap();
+        assert_eq!(engine.eval_str("count(50)"), Ok(Object::Integer(50)));
+        engine.set_max_call_depth(20);
+        assert_eq!(engine.eval_str("count(19)"), Ok(Object::Integer(19)));
+        assert_eq!(engine.eval_str("count(20)"), Err(EngineError::Runtime("maximum call depth exceeded".to_string())));
+    }
+
+    #[test]
+    fn test_drop_releases_globals() {
+        let state = Rc::new(Cell::new(0));
+        let mut engine = Engine::new();
+        let captured = Rc::clone(&state);
+        engine.register_fn("get", move |_: &[Object]| -> Result<Object, String> { Ok(Object::Integer(captured.get())) });
+        engine.eval_str("let f = fn() { get() };").unwrap();
+        assert_eq!(Rc::strong_count(&state), 2);
+        drop(engine);
+        assert_eq!(Rc::strong_count(&state), 1);
+    }
+
+    #[test]
+    fn test_register_fn_captures_host_state() {
+        let calls = Rc::new(Cell::new(0));
+        let mut engine = Engine::new();
+        let counter = Rc::clone(&calls);
+        engine.register_fn("tick", move |_: &[Object]| -> Result<Object, String> {
+            counter.set(counter.get() + 1);
+            Ok(Object::Integer(counter.get()))
+        });
+        assert_eq!(engine.eval_str("tick(); tick(); tick()"), Ok(Object::Integer(3)));
+        assert_eq!(calls.get(), 3);
+        // Registered functions shadow builtins of the same name.
+        engine.register_fn("len", |_: &[Object]| -> Result<Object, String> { Ok(Object::Null) });
+        assert_eq!(engine.eval_str("len([1])"), Ok(Object::Null));
+    }
+
+    #[test]
+    fn test_with_output() {
+        let captured = OutputBuffer::default();
+        let mut engine = Engine::with_output(captured.clone());
+        assert_eq!(engine.eval_str("puts(\"hello\", [1, 2]); let f = fn(x) { puts(x) }; f(3); 4"), Ok(Object::Integer(4)));
+        assert_eq!(String::from_utf8(captured.take()).unwrap(), "hello\n[1, 2]\n3\n");
+    }
+}
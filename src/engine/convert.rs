@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use crate::eval::object::{HashKey, Object};
+
+/// Converts a Monkey value into a Rust value, failing if the value has the wrong shape.
+pub trait FromObject: Sized {
+    /// Describes the values this type accepts, such as `ARRAY of INTEGER`, for error messages.
+    fn expected() -> String;
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError>;
+}
+
+/// Converts a Rust value into a Monkey value.
+pub trait IntoObject {
+    fn into_object(self) -> Object;
+}
+
+/// A value that did not match the type it was converted to. `location` says where inside the
+/// value the mismatch was, such as `[1]["name"]`, and is empty when it was the value itself.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConversionError {
+    pub expected: String,
+    pub found: &'static str,
+    pub location: String,
+}
+
+impl ConversionError {
+    pub fn new<T: FromObject>(found: &Object) -> Self {
+        Self { expected: T::expected(), found: found.type_name(), location: String::new() }
+    }
+
+    /// Records that the mismatch happened inside the element or entry at `location`.
+    fn within(mut self, location: String) -> Self {
+        self.location.insert_str(0, &location);
+        self
+    }
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.location.is_empty() {
+            write!(f, "expected {}, got {}", self.expected, self.found)
+        } else {
+            write!(f, "expected {} at {}, got {}", self.expected, self.location, self.found)
+        }
+    }
+}
+
+impl FromObject for Object {
+    fn expected() -> String {
+        "any value".to_string()
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        Ok(object.clone())
+    }
+}
+
+impl IntoObject for Object {
+    fn into_object(self) -> Object {
+        self
+    }
+}
+
+impl FromObject for i64 {
+    fn expected() -> String {
+        "INTEGER".to_string()
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        match object {
+            Object::Integer(value) => Ok(*value),
+            other => Err(ConversionError::new::<Self>(other)),
+        }
+    }
+}
+
+impl IntoObject for i64 {
+    fn into_object(self) -> Object {
+        Object::Integer(self)
+    }
+}
+
+/// Integers are accepted where a float is expected, as in Monkey arithmetic.
+impl FromObject for f64 {
+    fn expected() -> String {
+        "FLOAT".to_string()
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        match object {
+            Object::Float(value) => Ok(*value),
+            Object::Integer(value) => Ok(*value as f64),
+            other => Err(ConversionError::new::<Self>(other)),
+        }
+    }
+}
+
+impl IntoObject for f64 {
+    fn into_object(self) -> Object {
+        Object::Float(self)
+    }
+}
+
+impl FromObject for bool {
+    fn expected() -> String {
+        "BOOLEAN".to_string()
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        match object {
+            Object::Boolean(value) => Ok(*value),
+            other => Err(ConversionError::new::<Self>(other)),
+        }
+    }
+}
+
+impl IntoObject for bool {
+    fn into_object(self) -> Object {
+        Object::Boolean(self)
+    }
+}
+
+impl FromObject for String {
+    fn expected() -> String {
+        "STRING".to_string()
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        match object {
+            Object::String(value) => Ok(value.clone()),
+            other => Err(ConversionError::new::<Self>(other)),
+        }
+    }
+}
+
+impl IntoObject for String {
+    fn into_object(self) -> Object {
+        Object::String(self)
+    }
+}
+
+impl IntoObject for &str {
+    fn into_object(self) -> Object {
+        Object::String(self.to_string())
+    }
+}
+
+impl IntoObject for () {
+    fn into_object(self) -> Object {
+        Object::Null
+    }
+}
+
+impl<T: FromObject> FromObject for Vec<T> {
+    fn expected() -> String {
+        format!("ARRAY of {}", T::expected())
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        let Object::Array(elements) = object else {
+            return Err(ConversionError::new::<Self>(object));
+        };
+        elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| T::from_object(element).map_err(|error| error.within(format!("[{}]", index))))
+            .collect()
+    }
+}
+
+impl<T: IntoObject> IntoObject for Vec<T> {
+    fn into_object(self) -> Object {
+        Object::Array(self.into_iter().map(IntoObject::into_object).collect())
+    }
+}
+
+impl<T: FromObject> FromObject for HashMap<String, T> {
+    fn expected() -> String {
+        format!("HASH of STRING to {}", T::expected())
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        let Object::Hash(pairs) = object else {
+            return Err(ConversionError::new::<Self>(object));
+        };
+        pairs
+            .iter()
+            .map(|(key, value)| {
+                let location = format!("[{}]", key);
+                match key {
+                    HashKey::String(key) => T::from_object(value)
+                        .map(|value| (key.clone(), value))
+                        .map_err(|error| error.within(location)),
+                    HashKey::Integer(_) => Err(ConversionError { expected: "STRING key".to_string(), found: "INTEGER", location }),
+                    HashKey::Boolean(_) => Err(ConversionError { expected: "STRING key".to_string(), found: "BOOLEAN", location }),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<T: IntoObject> IntoObject for HashMap<String, T> {
+    fn into_object(self) -> Object {
+        Object::Hash(self.into_iter().map(|(key, value)| (HashKey::String(key), value.into_object())).collect())
+    }
+}
+
+/// `null` converts to `None`; anything else must convert to `T`.
+impl<T: FromObject> FromObject for Option<T> {
+    fn expected() -> String {
+        format!("{} or NULL", T::expected())
+    }
+
+    fn from_object(object: &Object) -> Result<Self, ConversionError> {
+        match object {
+            Object::Null => Ok(None),
+            other => T::from_object(other).map(Some).map_err(|mut error| {
+                if error.location.is_empty() {
+                    error.expected = Self::expected();
+                }
+                error
+            }),
+        }
+    }
+}
+
+impl<T: IntoObject> IntoObject for Option<T> {
+    fn into_object(self) -> Object {
+        match self {
+            Some(value) => value.into_object(),
+            None => Object::Null,
+        }
+    }
+}
+
+/// A native function returning `Err` raises its message as a Monkey error.
+impl<T: IntoObject, E: Display> IntoObject for Result<T, E> {
+    fn into_object(self) -> Object {
+        match self {
+            Ok(value) => value.into_object(),
+            Err(error) => Object::Error(error.to_string()),
+        }
+    }
+}
+
+/// Tuples convert to and from arrays of the same length.
+macro_rules! tuple_conversions {
+    ($len:expr; $($name:ident $index:tt),+) => {
+        impl<$($name: FromObject),+> FromObject for ($($name,)+) {
+            fn expected() -> String {
+                let elements: Vec<String> = vec![$($name::expected()),+];
+                format!("ARRAY of [{}]", elements.join(", "))
+            }
+
+            fn from_object(object: &Object) -> Result<Self, ConversionError> {
+                match object {
+                    Object::Array(elements) if elements.len() == $len => Ok(($(
+                        $name::from_object(&elements[$index]).map_err(|error| error.within(format!("[{}]", $index)))?,
+                    )+)),
+                    other => Err(ConversionError::new::<Self>(other)),
+                }
+            }
+        }
+
+        impl<$($name: IntoObject),+> IntoObject for ($($name,)+) {
+            fn into_object(self) -> Object {
+                Object::Array(vec![$(self.$index.into_object()),+])
+            }
+        }
+    };
+}
+
+tuple_conversions!(2; A 0, B 1);
+tuple_conversions!(3; A 0, B 1, C 2);
+tuple_conversions!(4; A 0, B 1, C 2, D 3);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::engine::convert::{ConversionError, FromObject, IntoObject};
+    use crate::eval::object::{HashKey, Object};
+
+    fn round_trip<T: FromObject + IntoObject + Clone + PartialEq + std::fmt::Debug>(value: T) {
+        assert_eq!(T::from_object(&value.clone().into_object()), Ok(value));
+    }
+
+    #[test]
+    fn test_round_trips() {
+        round_trip(42i64);
+        round_trip(1.5f64);
+        round_trip(true);
+        round_trip("héllo".to_string());
+        round_trip(vec![1i64, 2, 3]);
+        round_trip(vec![vec!["a".to_string()], vec![]]);
+        round_trip(HashMap::from([("one".to_string(), 1i64), ("two".to_string(), 2)]));
+        round_trip(Some(5i64));
+        round_trip(None::<i64>);
+        round_trip((1i64, "x".to_string()));
+        round_trip((true, vec![Some(1i64), None], 2.5f64, Object::Null));
+    }
+
+    #[test]
+    fn test_into_object() {
+        assert_eq!("s".into_object(), Object::String("s".to_string()));
+        assert_eq!(().into_object(), Object::Null);
+        assert_eq!((1i64, false).into_object(), Object::Array(vec![Object::Integer(1), Object::Boolean(false)]));
+        assert_eq!(Ok::<i64, String>(1).into_object(), Object::Integer(1));
+        assert_eq!(Err::<i64, _>("bad input").into_object(), Object::Error("bad input".to_string()));
+        assert_eq!(f64::from_object(&Object::Integer(2)), Ok(2.0));
+    }
+
+    #[test]
+    fn test_conversion_errors() {
+        let error = |expected: &str, found: &'static str, location: &str| {
+            ConversionError { expected: expected.to_string(), found, location: location.to_string() }
+        };
+        assert_eq!(i64::from_object(&Object::Boolean(true)).unwrap_err(), error("INTEGER", "BOOLEAN", ""));
+        assert_eq!(Vec::<i64>::from_object(&Object::Integer(1)).unwrap_err(), error("ARRAY of INTEGER", "INTEGER", ""));
+        let nested = Object::Array(vec![Object::Array(vec![]), Object::Array(vec![Object::Integer(1), Object::Null])]);
+        assert_eq!(Vec::<Vec<i64>>::from_object(&nested).unwrap_err(), error("INTEGER", "NULL", "[1][1]"));
+        assert_eq!(Option::<String>::from_object(&Object::Integer(1)).unwrap_err(), error("STRING or NULL", "INTEGER", ""));
+        let hash = Object::Hash(HashMap::from([(HashKey::String("a".to_string()), Object::Boolean(true))]));
+        assert_eq!(HashMap::<String, i64>::from_object(&hash).unwrap_err(), error("INTEGER", "BOOLEAN", "[\"a\"]"));
+        let hash = Object::Hash(HashMap::from([(HashKey::Integer(1), Object::Integer(1))]));
+        assert_eq!(HashMap::<String, i64>::from_object(&hash).unwrap_err(), error("STRING key", "INTEGER", "[1]"));
+        let pair = Object::Array(vec![Object::Integer(1)]);
+        assert_eq!(<(i64, i64)>::from_object(&pair).unwrap_err(), error("ARRAY of [INTEGER, INTEGER]", "ARRAY", ""));
+        assert_eq!(
+            Vec::<i64>::from_object(&Object::Array(vec![Object::String("x".to_string())])).unwrap_err().to_string(),
+            "expected INTEGER at [0], got STRING"
+        );
+    }
+}
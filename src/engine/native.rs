@@ -0,0 +1,74 @@
+use crate::engine::convert::{FromObject, IntoObject};
+use crate::eval::object::{NativeFn, Object};
+
+/// A Rust function that can be registered with `Engine::register_fn`. `Args` only tells the
+/// implementations apart: functions taking the raw argument slice use `RawArguments`, while
+/// typed functions such as `fn(i64, String) -> Vec<i64>` use the tuple of their parameter types.
+pub trait IntoNativeFn<Args> {
+    fn into_native_fn(self, name: &str) -> Box<NativeFn>;
+}
+
+/// Marks functions that unpack their `&[Object]` arguments themselves.
+pub struct RawArguments;
+
+impl<F, R> IntoNativeFn<RawArguments> for F
+where
+    F: Fn(&[Object]) -> R + 'static,
+    R: IntoObject,
+{
+    fn into_native_fn(self, _name: &str) -> Box<NativeFn> {
+        Box::new(move |arguments| self(arguments).into_object())
+    }
+}
+
+fn wrong_number_of_arguments(expected: usize, arguments: &[Object]) -> Object {
+    Object::Error(format!("wrong number of arguments: expected {}, got {}", expected, arguments.len()))
+}
+
+impl<F, R> IntoNativeFn<()> for F
+where
+    F: Fn() -> R + 'static,
+    R: IntoObject,
+{
+    fn into_native_fn(self, _name: &str) -> Box<NativeFn> {
+        Box::new(move |arguments| match arguments {
+            [] => self().into_object(),
+            _ => wrong_number_of_arguments(0, arguments),
+        })
+    }
+}
+
+/// Implements `IntoNativeFn` for functions of each arity, converting every argument before the
+/// call and reporting the first one that does not convert.
+macro_rules! typed_native_fn {
+    ($len:expr; $($name:ident $index:tt),+) => {
+        impl<F, R, $($name),+> IntoNativeFn<($($name,)+)> for F
+        where
+            F: Fn($($name),+) -> R + 'static,
+            R: IntoObject,
+            $($name: FromObject),+
+        {
+            fn into_native_fn(self, name: &str) -> Box<NativeFn> {
+                let name = name.to_string();
+                Box::new(move |arguments| {
+                    if arguments.len() != $len {
+                        return wrong_number_of_arguments($len, arguments);
+                    }
+                    self($(match $name::from_object(&arguments[$index]) {
+                        Ok(value) => value,
+                        Err(error) => {
+                            return Object::Error(format!("argument {} to `{}`: {}", $index + 1, name, error));
+                        }
+                    }),+)
+                    .into_object()
+                })
+            }
+        }
+    };
+}
+
+typed_native_fn!(1; A 0);
+typed_native_fn!(2; A 0, B 1);
+typed_native_fn!(3; A 0, B 1, C 2);
+typed_native_fn!(4; A 0, B 1, C 2, D 3);
+typed_native_fn!(5; A 0, B 1, C 2, D 3, E 4);
@@ -0,0 +1,656 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::ast::{BlockStatement, Expression, LetStatement, Program, ReturnStatement, Statement};
+use crate::eval::environment::Environment;
+use crate::eval::object::{Function, Object};
+
+pub mod builtins;
+pub mod environment;
+pub mod object;
+
+/// How deeply Monkey function calls may nest unless the environment sets another limit. Monkey
+/// has no loops, so this bounds how far a script can iterate by recursion.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 10_000;
+
+/// A Rust stack big enough to parse deeply nested input and evaluate `DEFAULT_MAX_CALL_DEPTH`
+/// nested calls, even in a debug build. Programs should evaluate on a thread with this much
+/// stack, as the main thread and other spawned threads have far less.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+pub fn eval_program(program: &Program, env: &mut Environment) -> Object {
+    let mut result = Object::Null;
+    for statement in &program.statements {
+        result = eval_statement(statement, env);
+        match result {
+            Object::ReturnValue(value) => return *value,
+            Object::Error(_) => return result,
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Evaluates a block without unwrapping return values, so a `return` inside nested blocks
+/// stops every enclosing block too.
+pub fn eval_block_statement(block: &BlockStatement, env: &mut Environment) -> Object {
+    let mut result = Object::Null;
+    for statement in &block.statements {
+        result = eval_statement(statement, env);
+        if matches!(result, Object::ReturnValue(_) | Object::Error(_)) {
+            return result;
+        }
+    }
+    result
+}
+
+pub fn eval_statement(statement: &Statement, env: &mut Environment) -> Object {
+    match statement {
+        Statement::Let(LetStatement { name, value, .. }) => {
+            let value = eval_expression(value, env);
+            if value.is_error() {
+                return value;
+            }
+            env.set(&name.name, value);
+            Object::Null
+        }
+        Statement::Return(ReturnStatement { return_value, .. }) => {
+            let value = eval_expression(return_value, env);
+            if value.is_error() {
+                return value;
+            }
+            Object::ReturnValue(Box::new(value))
+        }
+        Statement::Expression(expression_statement) => eval_expression(&expression_statement.expression, env),
+    }
+}
+
+pub fn eval_expression(expression: &Expression, env: &mut Environment) -> Object {
+    match expression {
+        Expression::Id(id) => match env.get(&id.name).or_else(|| builtins::lookup(&id.name)) {
+            Some(value) => value,
+            None => Object::Error(format!("identifier not found: {}", id.name)),
+        },
+        Expression::Lit(lit) => Object::Error(format!("cannot evaluate literal: {}", lit.value)),
+        Expression::Integer(int) => Object::Integer(int.value),
+        Expression::Float(float) => Object::Float(float.value),
+        Expression::Boolean(boolean) => Object::Boolean(boolean.value),
+        Expression::StringLiteral(string) => Object::String(string.value.clone()),
+        Expression::Prefix { operator, right, .. } => {
+            let right = eval_expression(right, env);
+            if right.is_error() {
+                return right;
+            }
+            eval_prefix_expression(operator, right)
+        }
+        Expression::Infix { left, operator, right, .. } => {
+            let left = eval_expression(left, env);
+            if left.is_error() {
+                return left;
+            }
+            // `&&` and `||` only evaluate their right side when the left does not decide the result.
+            match (operator.as_str(), left.is_truthy()) {
+                ("&&", false) => return Object::Boolean(false),
+                ("||", true) => return Object::Boolean(true),
+                ("&&" | "||", _) => {
+                    let right = eval_expression(right, env);
+                    if right.is_error() {
+                        return right;
+                    }
+                    return Object::Boolean(right.is_truthy());
+                }
+                _ => {}
+            }
+            let right = eval_expression(right, env);
+            if right.is_error() {
+                return right;
+            }
+            eval_infix_expression(operator, left, right)
+        }
+        Expression::If { condition, consequence, alternative, .. } => {
+            let condition = eval_expression(condition, env);
+            if condition.is_error() {
+                return condition;
+            }
+            if condition.is_truthy() {
+                eval_block_statement(consequence, env)
+            } else if let Some(alternative) = alternative {
+                eval_block_statement(alternative, env)
+            } else {
+                Object::Null
+            }
+        }
+        Expression::FunctionLiteral { parameters, body, .. } => Object::Function(Rc::new(Function {
+            parameters: parameters.clone(),
+            body: body.clone(),
+            env: env.clone(),
+        })),
+        Expression::Call { function, arguments, .. } => {
+            let function = eval_expression(function, env);
+            if function.is_error() {
+                return function;
+            }
+            match eval_expressions(arguments, env) {
+                Ok(arguments) => apply_function(function, arguments, env),
+                Err(error) => error,
+            }
+        }
+        Expression::Array { elements, .. } => match eval_expressions(elements, env) {
+            Ok(elements) => Object::Array(elements),
+            Err(error) => error,
+        },
+        Expression::Hash { pairs, .. } => eval_hash_literal(pairs, env),
+        Expression::Index { left, index, .. } => {
+            let left = eval_expression(left, env);
+            if left.is_error() {
+                return left;
+            }
+            let index = eval_expression(index, env);
+            if index.is_error() {
+                return index;
+            }
+            eval_index_expression(left, index)
+        }
+    }
+}
+
+/// Evaluates `expressions` from left to right, stopping at the first error.
+fn eval_expressions(expressions: &[Expression], env: &mut Environment) -> Result<Vec<Object>, Object> {
+    let mut evaluated = Vec::with_capacity(expressions.len());
+    for expression in expressions {
+        let value = eval_expression(expression, env);
+        if value.is_error() {
+            return Err(value);
+        }
+        evaluated.push(value);
+    }
+    Ok(evaluated)
+}
+
+/// Calls `function` from `env`. Calls nested deeper than the environment's maximum call depth
+/// fail with an error rather than overflowing the Rust stack.
+pub fn apply_function(function: Object, arguments: Vec<Object>, env: &Environment) -> Object {
+    let function = match function {
+        Object::Function(function) => function,
+        Object::Builtin(builtin) => return (builtin.function)(&arguments, env),
+        Object::Native(native) => return (native.function)(&arguments),
+        other => return Object::Error(format!("not a function: {}", other.type_name())),
+    };
+    if function.parameters.len() != arguments.len() {
+        return Object::Error(format!(
+            "wrong number of arguments: expected {}, got {}",
+            function.parameters.len(),
+            arguments.len()
+        ));
+    }
+    let depth = env.call_depth();
+    if depth >= env.max_call_depth() {
+        return Object::Error("maximum call depth exceeded".to_string());
+    }
+    let mut extended_env = Environment::new_enclosed(&function.env);
+    for (parameter, argument) in function.parameters.iter().zip(arguments) {
+        extended_env.set(&parameter.name, argument);
+    }
+    env.set_call_depth(depth + 1);
+    let result = eval_block_statement(&function.body, &mut extended_env);
+    env.set_call_depth(depth);
+    match result {
+        Object::ReturnValue(value) => *value,
+        result => result,
+    }
+}
+
+fn eval_hash_literal(pairs: &[(Expression, Expression)], env: &mut Environment) -> Object {
+    let mut hash = HashMap::with_capacity(pairs.len());
+    for (key, value) in pairs {
+        let key = eval_expression(key, env);
+        if key.is_error() {
+            return key;
+        }
+        let Some(hash_key) = key.hash_key() else {
+            return Object::Error(format!("unusable as hash key: {}", key.type_name()));
+        };
+        let value = eval_expression(value, env);
+        if value.is_error() {
+            return value;
+        }
+        hash.insert(hash_key, value);
+    }
+    Object::Hash(hash)
+}
+
+/// Indexing outside an array's bounds, including with a negative index, gives null, as does
+/// looking up a key a hash does not contain.
+fn eval_index_expression(left: Object, index: Object) -> Object {
+    match (&left, &index) {
+        (Object::Array(elements), Object::Integer(index)) => usize::try_from(*index)
+            .ok()
+            .and_then(|index| elements.get(index))
+            .cloned()
+            .unwrap_or(Object::Null),
+        (Object::Hash(pairs), _) => match index.hash_key() {
+            Some(key) => pairs.get(&key).cloned().unwrap_or(Object::Null),
+            None => Object::Error(format!("unusable as hash key: {}", index.type_name())),
+        },
+        _ => Object::Error(format!("index operator not supported: {}[{}]", left.type_name(), index.type_name())),
+    }
+}
+
+fn eval_prefix_expression(operator: &str, right: Object) -> Object {
+    match (operator, &right) {
+        ("!", _) => Object::Boolean(!right.is_truthy()),
+        ("-", Object::Integer(value)) => match value.checked_neg() {
+            Some(value) => Object::Integer(value),
+            None => Object::Error(format!("integer overflow: -{}", value)),
+        },
+        ("-", Object::Float(value)) => Object::Float(-value),
+        _ => Object::Error(format!("unknown operator: {}{}", operator, right.type_name())),
+    }
+}
+
+fn eval_infix_expression(operator: &str, left: Object, right: Object) -> Object {
+    match (&left, &right) {
+        (Object::Integer(left), Object::Integer(right)) => eval_integer_infix_expression(operator, *left, *right),
+        // Mixing an integer with a float promotes the integer.
+        (Object::Float(left), Object::Float(right)) => eval_float_infix_expression(operator, *left, *right),
+        (Object::Integer(left), Object::Float(right)) => eval_float_infix_expression(operator, *left as f64, *right),
+        (Object::Float(left), Object::Integer(right)) => eval_float_infix_expression(operator, *left, *right as f64),
+        (Object::Boolean(left), Object::Boolean(right)) => match operator {
+            "==" => Object::Boolean(left == right),
+            "!=" => Object::Boolean(left != right),
+            _ => Object::Error(format!("unknown operator: BOOLEAN {} BOOLEAN", operator)),
+        },
+        (Object::String(left), Object::String(right)) => match operator {
+            "+" => Object::String(format!("{}{}", left, right)),
+            "==" => Object::Boolean(left == right),
+            "!=" => Object::Boolean(left != right),
+            _ => Object::Error(format!("unknown operator: STRING {} STRING", operator)),
+        },
+        _ if left.type_name() != right.type_name() => {
+            Object::Error(format!("type mismatch: {} {} {}", left.type_name(), operator, right.type_name()))
+        }
+        _ => Object::Error(format!("unknown operator: {} {} {}", left.type_name(), operator, right.type_name())),
+    }
+}
+
+fn eval_integer_infix_expression(operator: &str, left: i64, right: i64) -> Object {
+    let result = match operator {
+        "+" => left.checked_add(right),
+        "-" => left.checked_sub(right),
+        "*" => left.checked_mul(right),
+        "/" if right == 0 => return Object::Error("division by zero".to_string()),
+        "/" => left.checked_div(right),
+        "%" if right == 0 => return Object::Error("division by zero".to_string()),
+        "%" => left.checked_rem(right),
+        "<" => return Object::Boolean(left < right),
+        ">" => return Object::Boolean(left > right),
+        "<=" => return Object::Boolean(left <= right),
+        ">=" => return Object::Boolean(left >= right),
+        "==" => return Object::Boolean(left == right),
+        "!=" => return Object::Boolean(left != right),
+        _ => return Object::Error(format!("unknown operator: INTEGER {} INTEGER", operator)),
+    };
+    match result {
+        Some(value) => Object::Integer(value),
+        None => Object::Error(format!("integer overflow: {} {} {}", left, operator, right)),
+    }
+}
+
+/// Float arithmetic follows IEEE 754, so dividing by zero gives an infinity rather than an error.
+fn eval_float_infix_expression(operator: &str, left: f64, right: f64) -> Object {
+    match operator {
+        "+" => Object::Float(left + right),
+        "-" => Object::Float(left - right),
+        "*" => Object::Float(left * right),
+        "/" => Object::Float(left / right),
+        "%" => Object::Float(left % right),
+        "<" => Object::Boolean(left < right),
+        ">" => Object::Boolean(left > right),
+        "<=" => Object::Boolean(left <= right),
+        ">=" => Object::Boolean(left >= right),
+        "==" => Object::Boolean(left == right),
+        "!=" => Object::Boolean(left != right),
+        _ => Object::Error(format!("unknown operator: FLOAT {} FLOAT", operator)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::thread;
+
+    use crate::eval::environment::Environment;
+    use crate::eval::{eval_program, DEFAULT_MAX_CALL_DEPTH, STACK_SIZE};
+    use crate::eval::object::{HashKey, Object};
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn eval(input: &str) -> Object {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let (program, errors) = parser.parse_program();
+        assert!(errors.is_empty(), "Unexpected parse errors for {:?}: {:?}", input, errors);
+        eval_program(&program, &mut Environment::new())
+    }
+
+    #[test]
+    fn test_eval_integer_expression() {
+        let tests = [
+            ("5", 5),
+            ("-10", -10),
+            ("5 + 5 + 5 + 5 - 10", 10),
+            ("2 * 2 * 2 * 2 * 2", 32),
+            ("-50 + 100 + -50", 0),
+            ("20 + 2 * -10", 0),
+            ("50 / 2 * 2 + 10", 60),
+            ("3 * 3 * 3 + 10", 37),
+            ("17 % 5", 2),
+            ("-17 % 5", -2),
+            ("2 + 7 % 4 * 3", 11),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), Object::Integer(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_float_expression() {
+        let tests = [
+            ("1.5", Object::Float(1.5)),
+            ("-2.5e2", Object::Float(-250.0)),
+            ("0.5 + 0.25 * 2.0", Object::Float(1.0)),
+            ("1 + 0.5", Object::Float(1.5)),
+            ("3.0 / 2", Object::Float(1.5)),
+            ("1.0 / 0", Object::Float(f64::INFINITY)),
+            ("0x10 + 0b1 + 1_000", Object::Integer(1017)),
+            ("1.5 < 2", Object::Boolean(true)),
+            ("2 == 2.0", Object::Boolean(true)),
+            ("0.1 + 0.2 != 0.3", Object::Boolean(true)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_boolean_expression() {
+        let tests = [
+            ("1 < 2", true),
+            ("1 > 2", false),
+            ("1 == 1", true),
+            ("1 != 1", false),
+            ("!5", false),
+            ("!!5", true),
+            ("1 < 2 == !0", false),
+            ("!5 == !1", true),
+            ("true", true),
+            ("false", false),
+            ("true == true", true),
+            ("true != false", true),
+            ("(1 < 2) == true", true),
+            ("(1 > 2) == true", false),
+            ("!true", false),
+            ("1 <= 1", true),
+            ("2 <= 1", false),
+            ("1 >= 2", false),
+            ("1.5 >= 1", true),
+            ("true && false", false),
+            ("true || false", true),
+            ("1 < 2 && 2 < 3", true),
+            ("false || 1 > 2", false),
+            ("1 && \"a\"", true),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), Object::Boolean(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_logical_operators_short_circuit() {
+        let tests = [
+            ("false && foobar", Object::Boolean(false)),
+            ("true || foobar", Object::Boolean(true)),
+            ("let n = 0; n != 0 && 10 / n > 1", Object::Boolean(false)),
+            ("7.5 % 2", Object::Float(1.5)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_let_and_return_statements() {
+        let tests = [
+            ("let a = 5; a;", 5),
+            ("let a = 5 * 5; a;", 25),
+            ("let a = 5; let b = a; let c = a + b + 5; c;", 15),
+            ("return 10; 9;", 10),
+            ("9; return 2 * 5; 9;", 10),
+            ("let a = 2; return a * 5; a;", 10),
+            ("let Point = 3; let x2 = Point * 2; let _private = x2 + 1; _private;", 7),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), Object::Integer(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_if_else_expression() {
+        let tests = [
+            ("if (true) { 10 }", Object::Integer(10)),
+            ("if (false) { 10 }", Object::Null),
+            ("if (1) { 10 }", Object::Integer(10)),
+            ("if (1 < 2) { 10 }", Object::Integer(10)),
+            ("if (1 > 2) { 10 }", Object::Null),
+            ("if (1 > 2) { 10 } else { 20 }", Object::Integer(20)),
+            ("if (1 < 2) { 10 } else { 20 }", Object::Integer(10)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_nested_return() {
+        let input = r#"
+        if (10 > 1) {
+            if (10 > 1) {
+                return 10;
+            }
+            return 1;
+        }
+        "#;
+        assert_eq!(eval(input), Object::Integer(10));
+        assert_eq!(eval("if (10 > 1) { true + false; 10 }"), Object::Error("unknown operator: BOOLEAN + BOOLEAN".to_string()));
+    }
+
+    #[test]
+    fn test_eval_function_object() {
+        match eval("fn(x) { x + 2; };") {
+            Object::Function(function) => {
+                assert_eq!(function.parameters.len(), 1);
+                assert_eq!(function.parameters[0].name, "x");
+                assert_eq!(Object::Function(function).to_string(), "fn(x) { (x + 2) }");
+            }
+            other => panic!("Expected function, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_eval_function_application() {
+        let tests = [
+            ("let identity = fn(x) { x; }; identity(5);", 5),
+            ("let identity = fn(x) { return x; }; identity(5);", 5),
+            ("let double = fn(x) { x * 2; }; double(5);", 10),
+            ("let add = fn(x, y) { x + y; }; add(5, 5);", 10),
+            ("let add = fn(x, y) { x + y; }; add(5 + 5, add(5, 5));", 20),
+            ("fn(x) { x; }(5)", 5),
+            ("let fib = fn(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }; fib(15);", 610),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), Object::Integer(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_closures() {
+        let input = r#"
+        let newAdder = fn(x) {
+            fn(y) { x + y };
+        };
+        let addTwo = newAdder(2);
+        addTwo(3);
+        "#;
+        assert_eq!(eval(input), Object::Integer(5));
+    }
+
+    #[test]
+    fn test_eval_call_depth_limit() {
+        // Reaching the default limit needs more stack than the test harness gives each thread.
+        let deep = thread::Builder::new().stack_size(STACK_SIZE).spawn(|| {
+            let count = "let count = fn(n) { if (n == 0) { 0 } else { 1 + count(n - 1) } };";
+            let limit = DEFAULT_MAX_CALL_DEPTH;
+            assert_eq!(eval(&format!("{} count({})", count, limit - 1)), Object::Integer(limit as i64 - 1));
+            assert_eq!(eval(&format!("{} count({})", count, limit)), Object::Error("maximum call depth exceeded".to_string()));
+            assert_eq!(eval("let forever = fn(n) { forever(n + 1) }; forever(0)"), Object::Error("maximum call depth exceeded".to_string()));
+            // The depth unwinds with the calls, so later calls start from zero again.
+            assert_eq!(eval(&format!("{} let deep = count({}); count(5)", count, limit - 1)), Object::Integer(5));
+        });
+        deep.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn test_eval_configured_call_depth_limit() {
+        let mut env = Environment::new();
+        env.set_max_call_depth(10);
+        let inputs = [
+            ("let count = fn(n) { if (n == 0) { 0 } else { 1 + count(n - 1) } }; count(9)", Object::Integer(9)),
+            ("count(10)", Object::Error("maximum call depth exceeded".to_string())),
+        ];
+        for (input, expected) in inputs {
+            let mut parser = Parser::new(Lexer::new(input));
+            let (program, _) = parser.parse_program();
+            assert_eq!(eval_program(&program, &mut env), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_function_scope() {
+        let tests = [
+            ("let x = 1; let f = fn() { let x = 2; x }; f() + x", 3),
+            ("let x = 1; let f = fn(x) { x * 10 }; f(5) + x", 51),
+            ("let f = fn() { let inner = 7; inner }; f(); let inner = 1; inner", 1),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), Object::Integer(expected), "{}", input);
+        }
+        assert_eq!(eval("let f = fn() { let hidden = 7; hidden }; f(); hidden"), Object::Error("identifier not found: hidden".to_string()));
+    }
+
+    #[test]
+    fn test_eval_arrays() {
+        let tests = [
+            ("[1, 2 * 2, 3 + 3]", Object::Array(vec![Object::Integer(1), Object::Integer(4), Object::Integer(6)])),
+            ("[]", Object::Array(vec![])),
+            ("[1, 2, 3][0]", Object::Integer(1)),
+            ("[1, 2, 3][1 + 1]", Object::Integer(3)),
+            ("let i = 0; [1][i]", Object::Integer(1)),
+            ("let myArray = [1, 2, 3]; myArray[0] + myArray[1] + myArray[2]", Object::Integer(6)),
+            ("let myArray = [1, 2, 3]; let i = myArray[0]; myArray[i]", Object::Integer(2)),
+            ("[[1, 2], [3]][0][1]", Object::Integer(2)),
+            ("[1, 2, 3][3]", Object::Null),
+            ("[1, 2, 3][-1]", Object::Null),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), expected, "{}", input);
+        }
+        assert_eq!(eval(r#"[1, "a, b", [true], fn(x) { x }]"#).to_string(), r#"[1, "a, b", [true], fn(x) { x }]"#);
+    }
+
+    #[test]
+    fn test_eval_hashes() {
+        let input = r#"
+        let two = "two";
+        {"one": 10 - 9, two: 1 + 1, "thr" + "ee": 6 / 2, 4: 4, true: 5, false: 6}
+        "#;
+        let expected = HashMap::from([
+            (HashKey::String("one".to_string()), Object::Integer(1)),
+            (HashKey::String("two".to_string()), Object::Integer(2)),
+            (HashKey::String("three".to_string()), Object::Integer(3)),
+            (HashKey::Integer(4), Object::Integer(4)),
+            (HashKey::Boolean(true), Object::Integer(5)),
+            (HashKey::Boolean(false), Object::Integer(6)),
+        ]);
+        assert_eq!(eval(input), Object::Hash(expected));
+
+        let tests = [
+            (r#"{"foo": 5}["foo"]"#, Object::Integer(5)),
+            (r#"{"foo": 5}["bar"]"#, Object::Null),
+            (r#"let key = "foo"; {"foo": 5}[key]"#, Object::Integer(5)),
+            (r#"{}["foo"]"#, Object::Null),
+            ("{5: 5}[5]", Object::Integer(5)),
+            ("{true: 5}[true]", Object::Integer(5)),
+            ("{1: 1, 1: 2}[1]", Object::Integer(2)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), expected, "{}", input);
+        }
+        assert_eq!(eval(r#"{"b": [1, "x"], "a": {2: true}, 1: 0}"#).to_string(), r#"{1: 0, "a": {2: true}, "b": [1, "x"]}"#);
+    }
+
+    #[test]
+    fn test_eval_strings() {
+        let tests = [
+            (r#""Hello World!""#, Object::String("Hello World!".to_string())),
+            (r#""Hello" + " " + "World!""#, Object::String("Hello World!".to_string())),
+            (r#"let greet = fn(name) { "Hi, " + name }; greet("\u{1F600}")"#, Object::String("Hi, \u{1F600}".to_string())),
+            (r#""a" == "a""#, Object::Boolean(true)),
+            (r#""a" != "a""#, Object::Boolean(false)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let tests = [
+            ("5 + !5;", "type mismatch: INTEGER + BOOLEAN"),
+            ("5 + !5; 5;", "type mismatch: INTEGER + BOOLEAN"),
+            ("-!5;", "unknown operator: -BOOLEAN"),
+            ("!5 + !5;", "unknown operator: BOOLEAN + BOOLEAN"),
+            ("return !5 * 1;", "type mismatch: BOOLEAN * INTEGER"),
+            ("foobar;", "identifier not found: foobar"),
+            ("1 / 0;", "division by zero"),
+            ("1 % 0;", "division by zero"),
+            ("(-9223372036854775807 - 1) % -1;", "integer overflow: -9223372036854775808 % -1"),
+            ("true && foobar;", "identifier not found: foobar"),
+            ("true <= false;", "unknown operator: BOOLEAN <= BOOLEAN"),
+            ("[1, 2][true]", "index operator not supported: ARRAY[BOOLEAN]"),
+            ("1[0]", "index operator not supported: INTEGER[INTEGER]"),
+            ("[1, foobar, 3]", "identifier not found: foobar"),
+            (r#"{"name": "Monkey"}[fn(x) { x }];"#, "unusable as hash key: FUNCTION"),
+            ("{[1]: 2}", "unusable as hash key: ARRAY"),
+            ("{1: foobar}", "identifier not found: foobar"),
+            ("9223372036854775807 + 1;", "integer overflow: 9223372036854775807 + 1"),
+            ("5(1);", "not a function: INTEGER"),
+            ("fn(x, y) { x }(1);", "wrong number of arguments: expected 2, got 1"),
+            (r#""Hello" - "World""#, "unknown operator: STRING - STRING"),
+            (r#""Hello" + 1"#, "type mismatch: STRING + INTEGER"),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), Object::Error(expected.to_string()), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_eval_with_seeded_environment() {
+        let lexer = Lexer::new("let doubled = base * 2; doubled + base");
+        let mut parser = Parser::new(lexer);
+        let (program, _) = parser.parse_program();
+        let mut env = Environment::new();
+        env.set("base", Object::Integer(21));
+        assert_eq!(eval_program(&program, &mut env), Object::Integer(63));
+        assert_eq!(env.get("doubled"), Some(Object::Integer(42)));
+    }
+}
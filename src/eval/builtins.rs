@@ -0,0 +1,201 @@
+use std::io::Write;
+
+use crate::eval::environment::Environment;
+use crate::eval::object::{Builtin, Object};
+
+/// The functions available to every program. A binding of the same name shadows a builtin.
+const BUILTINS: &[Builtin] = &[
+    Builtin { name: "len", function: len },
+    Builtin { name: "first", function: first },
+    Builtin { name: "last", function: last },
+    Builtin { name: "rest", function: rest },
+    Builtin { name: "push", function: push },
+    Builtin { name: "puts", function: puts },
+    Builtin { name: "type", function: type_of },
+    Builtin { name: "str", function: str },
+];
+
+pub fn lookup(name: &str) -> Option<Object> {
+    BUILTINS.iter().find(|builtin| builtin.name == name).map(|builtin| Object::Builtin(*builtin))
+}
+
+/// Checks the number of arguments, producing the same error as calling a Monkey function with
+/// the wrong number.
+fn expect_arguments(arguments: &[Object], expected: usize) -> Result<(), Object> {
+    if arguments.len() == expected {
+        Ok(())
+    } else {
+        Err(Object::Error(format!("wrong number of arguments: expected {}, got {}", expected, arguments.len())))
+    }
+}
+
+fn expect_array<'a>(name: &str, argument: &'a Object) -> Result<&'a [Object], Object> {
+    match argument {
+        Object::Array(elements) => Ok(elements),
+        other => Err(Object::Error(format!("argument to `{}` must be ARRAY, got {}", name, other.type_name()))),
+    }
+}
+
+/// `len(x)` is the number of characters in a string, elements in an array or entries in a hash.
+fn len(arguments: &[Object], _env: &Environment) -> Object {
+    if let Err(error) = expect_arguments(arguments, 1) {
+        return error;
+    }
+    match &arguments[0] {
+        Object::String(value) => Object::Integer(value.chars().count() as i64),
+        Object::Array(elements) => Object::Integer(elements.len() as i64),
+        Object::Hash(pairs) => Object::Integer(pairs.len() as i64),
+        other => Object::Error(format!("argument to `len` not supported, got {}", other.type_name())),
+    }
+}
+
+/// `first(array)` is the first element, or null for an empty array.
+fn first(arguments: &[Object], _env: &Environment) -> Object {
+    let elements = match expect_arguments(arguments, 1).and_then(|()| expect_array("first", &arguments[0])) {
+        Ok(elements) => elements,
+        Err(error) => return error,
+    };
+    elements.first().cloned().unwrap_or(Object::Null)
+}
+
+/// `last(array)` is the last element, or null for an empty array.
+fn last(arguments: &[Object], _env: &Environment) -> Object {
+    let elements = match expect_arguments(arguments, 1).and_then(|()| expect_array("last", &arguments[0])) {
+        Ok(elements) => elements,
+        Err(error) => return error,
+    };
+    elements.last().cloned().unwrap_or(Object::Null)
+}
+
+/// `rest(array)` is a new array of every element but the first, or null for an empty array.
+fn rest(arguments: &[Object], _env: &Environment) -> Object {
+    let elements = match expect_arguments(arguments, 1).and_then(|()| expect_array("rest", &arguments[0])) {
+        Ok(elements) => elements,
+        Err(error) => return error,
+    };
+    match elements.split_first() {
+        Some((_, rest)) => Object::Array(rest.to_vec()),
+        None => Object::Null,
+    }
+}
+
+/// `push(array, x)` is a new array with `x` appended; the original is left unchanged.
+fn push(arguments: &[Object], _env: &Environment) -> Object {
+    let elements = match expect_arguments(arguments, 2).and_then(|()| expect_array("push", &arguments[0])) {
+        Ok(elements) => elements,
+        Err(error) => return error,
+    };
+    let mut pushed = elements.to_vec();
+    pushed.push(arguments[1].clone());
+    Object::Array(pushed)
+}
+
+/// `puts(...)` prints each argument on its own line to the environment's output.
+fn puts(arguments: &[Object], env: &Environment) -> Object {
+    let mut output = env.output();
+    for argument in arguments {
+        if let Err(error) = writeln!(output, "{}", argument) {
+            return Object::Error(format!("cannot write output: {}", error));
+        }
+    }
+    Object::Null
+}
+
+/// `type(x)` is the name of the type of `x`, such as `"INTEGER"`.
+fn type_of(arguments: &[Object], _env: &Environment) -> Object {
+    if let Err(error) = expect_arguments(arguments, 1) {
+        return error;
+    }
+    Object::String(arguments[0].type_name().to_string())
+}
+
+/// `str(x)` is `x` formatted as it would be printed.
+fn str(arguments: &[Object], _env: &Environment) -> Object {
+    if let Err(error) = expect_arguments(arguments, 1) {
+        return error;
+    }
+    Object::String(arguments[0].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::eval::environment::{Environment, OutputBuffer};
+    use crate::eval::eval_program;
+    use crate::eval::object::Object;
+    use crate::lexer::Lexer;
+    use crate::parser::Parser;
+
+    fn eval(input: &str) -> Object {
+        let mut parser = Parser::new(Lexer::new(input));
+        let (program, errors) = parser.parse_program();
+        assert!(errors.is_empty(), "{:?}", errors);
+        eval_program(&program, &mut Environment::with_output(io::sink()))
+    }
+
+    fn array(elements: &[i64]) -> Object {
+        Object::Array(elements.iter().map(|element| Object::Integer(*element)).collect())
+    }
+
+    #[test]
+    fn test_builtins() {
+        let tests = [
+            (r#"len("")"#, Object::Integer(0)),
+            (r#"len("four")"#, Object::Integer(4)),
+            (r#"len("héllo")"#, Object::Integer(5)),
+            ("len([1, 2, 3])", Object::Integer(3)),
+            (r#"len({"a": 1})"#, Object::Integer(1)),
+            ("first([1, 2, 3])", Object::Integer(1)),
+            ("first([])", Object::Null),
+            ("last([1, 2, 3])", Object::Integer(3)),
+            ("last([])", Object::Null),
+            ("rest([1, 2, 3])", array(&[2, 3])),
+            ("rest(rest([1, 2, 3]))", array(&[3])),
+            ("rest([])", Object::Null),
+            ("push([], 1)", array(&[1])),
+            ("let a = [1]; let b = push(a, 2); a", array(&[1])),
+            ("let a = [1]; let b = push(a, 2); b", array(&[1, 2])),
+            ("puts(1, \"two\")", Object::Null),
+            ("type(1)", Object::String("INTEGER".to_string())),
+            ("type([])", Object::String("ARRAY".to_string())),
+            ("type(len)", Object::String("BUILTIN".to_string())),
+            (r#"str(12) + str(true) + str("!")"#, Object::String("12true!".to_string())),
+            (r#"str([1, "a"])"#, Object::String(r#"[1, "a"]"#.to_string())),
+            ("let len = fn(x) { 42 }; len([])", Object::Integer(42)),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_builtin_errors() {
+        let tests = [
+            ("len(1)", "argument to `len` not supported, got INTEGER"),
+            (r#"len("one", "two")"#, "wrong number of arguments: expected 1, got 2"),
+            ("first(1)", "argument to `first` must be ARRAY, got INTEGER"),
+            ("last()", "wrong number of arguments: expected 1, got 0"),
+            (r#"rest("abc")"#, "argument to `rest` must be ARRAY, got STRING"),
+            ("push([1])", "wrong number of arguments: expected 2, got 1"),
+            ("push(1, 1)", "argument to `push` must be ARRAY, got INTEGER"),
+            ("type(1, 2)", "wrong number of arguments: expected 1, got 2"),
+            ("str()", "wrong number of arguments: expected 1, got 0"),
+            ("len(foo)", "identifier not found: foo"),
+        ];
+        for (input, expected) in tests {
+            assert_eq!(eval(input), Object::Error(expected.to_string()), "{}", input);
+        }
+        assert_eq!(eval("len").to_string(), "builtin function len");
+    }
+
+    #[test]
+    fn test_puts() {
+        let captured = OutputBuffer::default();
+        let mut parser = Parser::new(Lexer::new(r#"puts(1, "two", [3]); puts()"#));
+        let (program, errors) = parser.parse_program();
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(eval_program(&program, &mut Environment::with_output(captured.clone())), Object::Null);
+        assert_eq!(String::from_utf8(captured.take()).unwrap(), "1\ntwo\n[3]\n");
+    }
+}
@@ -0,0 +1,208 @@
+use std::cell::{Cell, RefCell, RefMut};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io;
+use std::io::Write;
+use std::rc::Rc;
+
+use crate::eval::object::Object;
+use crate::eval::DEFAULT_MAX_CALL_DEPTH;
+
+/// A handle to a scope of bindings. Cloning the handle shares the scope, which is how function
+/// objects keep hold of the environment they were defined in.
+///
+/// Every scope enclosed in another shares its context: where `puts` writes, how deeply
+/// function calls are nested and how deeply they may nest.
+#[derive(Clone, Default)]
+pub struct Environment {
+    scope: Rc<RefCell<Scope>>,
+    context: Rc<Context>,
+}
+
+#[derive(Default)]
+struct Scope {
+    store: HashMap<String, Object>,
+    outer: Option<Environment>,
+}
+
+struct Context {
+    output: RefCell<Box<dyn Write>>,
+    call_depth: Cell<usize>,
+    max_call_depth: Cell<usize>,
+}
+
+impl Context {
+    fn new(output: Box<dyn Write>) -> Self {
+        Self {
+            output: RefCell::new(output),
+            call_depth: Cell::new(0),
+            max_call_depth: Cell::new(DEFAULT_MAX_CALL_DEPTH),
+        }
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new(Box::new(io::stdout()))
+    }
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an environment whose program output, such as from `puts`, goes to `output`
+    /// rather than standard output.
+    pub fn with_output(output: impl Write + 'static) -> Self {
+        Self {
+            scope: Rc::default(),
+            context: Rc::new(Context::new(Box::new(output))),
+        }
+    }
+
+    /// Creates a child scope whose lookups fall back to `outer`.
+    pub fn new_enclosed(outer: &Environment) -> Self {
+        Self {
+            scope: Rc::new(RefCell::new(Scope {
+                store: HashMap::new(),
+                outer: Some(outer.clone()),
+            })),
+            context: Rc::clone(&outer.context),
+        }
+    }
+
+    /// Where the program's output is written.
+    pub fn output(&self) -> RefMut<'_, Box<dyn Write>> {
+        self.context.output.borrow_mut()
+    }
+
+    /// The number of function calls currently being evaluated.
+    pub fn call_depth(&self) -> usize {
+        self.context.call_depth.get()
+    }
+
+    pub fn set_call_depth(&self, depth: usize) {
+        self.context.call_depth.set(depth);
+    }
+
+    /// How deeply function calls may nest before a call fails with an error.
+    pub fn max_call_depth(&self) -> usize {
+        self.context.max_call_depth.get()
+    }
+
+    /// Sets the call depth limit for this environment and every scope sharing its context. Each
+    /// call takes some Rust stack, so a higher limit needs a thread with a bigger stack.
+    pub fn set_max_call_depth(&self, depth: usize) {
+        self.context.max_call_depth.set(depth);
+    }
+
+    /// Looks `name` up in this scope, then in each enclosing scope in turn.
+    pub fn get(&self, name: &str) -> Option<Object> {
+        let scope = self.scope.borrow();
+        match scope.store.get(name) {
+            Some(value) => Some(value.clone()),
+            None => scope.outer.as_ref().and_then(|outer| outer.get(name)),
+        }
+    }
+
+    /// Binds `name` in this scope, shadowing any binding of the same name in enclosing scopes.
+    pub fn set(&mut self, name: &str, value: Object) {
+        self.scope.borrow_mut().store.insert(name.to_string(), value);
+    }
+
+    /// Removes every binding in this scope. A function bound in a scope holds a handle to that
+    /// scope, so the scope is only freed once its bindings are cleared.
+    pub fn clear(&mut self) {
+        let store = std::mem::take(&mut self.scope.borrow_mut().store);
+        // Dropped after the borrow ends, as dropping a value may release handles to this scope.
+        drop(store);
+    }
+}
+
+/// Program output kept in memory. Clones share the buffer, so one clone can be given to
+/// `Environment::with_output` while another reads back what the program printed.
+#[derive(Clone, Default)]
+pub struct OutputBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl OutputBuffer {
+    /// Removes and returns everything written so far.
+    pub fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.0.borrow_mut())
+    }
+}
+
+impl Write for OutputBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl PartialEq for Environment {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.scope, &other.scope)
+    }
+}
+
+impl Debug for Environment {
+    // Only the names are printed: a function bound in a scope refers back to that scope.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let scope = self.scope.borrow();
+        let mut names: Vec<&String> = scope.store.keys().collect();
+        names.sort();
+        f.debug_struct("Environment")
+            .field("names", &names)
+            .field("outer", &scope.outer)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::eval::environment::Environment;
+    use crate::eval::object::Object;
+
+    #[test]
+    fn test_enclosed_lookup_falls_back_to_outer() {
+        let mut outer = Environment::new();
+        outer.set("x", Object::Integer(1));
+        let mut inner = Environment::new_enclosed(&outer);
+        inner.set("y", Object::Integer(2));
+        assert_eq!(inner.get("x"), Some(Object::Integer(1)));
+        assert_eq!(inner.get("y"), Some(Object::Integer(2)));
+        assert_eq!(outer.get("y"), None);
+    }
+
+    #[test]
+    fn test_shadowing_does_not_leak() {
+        let mut outer = Environment::new();
+        outer.set("x", Object::Integer(1));
+        let mut inner = Environment::new_enclosed(&outer);
+        inner.set("x", Object::Boolean(true));
+        assert_eq!(inner.get("x"), Some(Object::Boolean(true)));
+        assert_eq!(outer.get("x"), Some(Object::Integer(1)));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut env = Environment::new();
+        env.set("x", Object::Integer(1));
+        env.clear();
+        assert_eq!(env.get("x"), None);
+    }
+
+    #[test]
+    fn test_clones_share_scope() {
+        let env = Environment::new();
+        let mut handle = env.clone();
+        handle.set("x", Object::Integer(1));
+        assert_eq!(env.get("x"), Some(Object::Integer(1)));
+        assert_eq!(env, handle);
+        assert_ne!(env, Environment::new());
+    }
+}
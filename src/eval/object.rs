@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::rc::Rc;
+
+use crate::ast::show::{quote, Show};
+use crate::ast::{BlockStatement, Identifier};
+use crate::eval::environment::Environment;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Object {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    String(String),
+    Array(Vec<Object>),
+    Hash(HashMap<HashKey, Object>),
+    Null,
+    ReturnValue(Box<Object>),
+    Error(String),
+    Function(Rc<Function>),
+    Builtin(Builtin),
+    Native(Rc<NativeFunction>),
+}
+
+/// The key of a hash entry. Only integers, booleans and strings can be keys.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+pub enum HashKey {
+    Integer(i64),
+    Boolean(bool),
+    String(String),
+}
+
+impl Display for HashKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HashKey::Integer(value) => write!(f, "{}", value),
+            HashKey::Boolean(value) => write!(f, "{}", value),
+            HashKey::String(value) => write!(f, "{}", quote(value)),
+        }
+    }
+}
+
+/// A function value: the literal's parameters and body together with the environment it was
+/// defined in.
+#[derive(Debug, PartialEq)]
+pub struct Function {
+    pub parameters: Vec<Identifier>,
+    pub body: BlockStatement,
+    pub env: Environment,
+}
+
+/// A function implemented by the host, such as `len`.
+#[derive(Debug, Clone, Copy)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub function: fn(&[Object], &Environment) -> Object,
+}
+
+// Builtins are identified by name; comparing function pointers is not reliable.
+impl PartialEq for Builtin {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+pub type NativeFn = dyn Fn(&[Object]) -> Object;
+
+/// A function registered by the embedding program, such as a closure passed to
+/// `Engine::register_fn`.
+pub struct NativeFunction {
+    pub name: String,
+    pub function: Box<NativeFn>,
+}
+
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Debug for NativeFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("NativeFunction").field("name", &self.name).finish_non_exhaustive()
+    }
+}
+
+impl Object {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Object::Integer(_) => "INTEGER",
+            Object::Float(_) => "FLOAT",
+            Object::Boolean(_) => "BOOLEAN",
+            Object::String(_) => "STRING",
+            Object::Array(_) => "ARRAY",
+            Object::Hash(_) => "HASH",
+            Object::Null => "NULL",
+            Object::ReturnValue(_) => "RETURN_VALUE",
+            Object::Error(_) => "ERROR",
+            Object::Function(_) => "FUNCTION",
+            Object::Builtin(_) | Object::Native(_) => "BUILTIN",
+        }
+    }
+
+    /// Everything except `false` and `null` counts as true in a condition.
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Object::Boolean(false) | Object::Null)
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, Object::Error(_))
+    }
+
+    /// Formats the value as it appears inside an array or hash, where strings are quoted so that
+    /// `["a, b"]` is not mistaken for two elements.
+    pub fn inspect(&self) -> String {
+        match self {
+            Object::String(value) => quote(value),
+            value => value.to_string(),
+        }
+    }
+
+    /// The key this value is stored under in a hash, or `None` if it cannot be a key.
+    pub fn hash_key(&self) -> Option<HashKey> {
+        match self {
+            Object::Integer(value) => Some(HashKey::Integer(*value)),
+            Object::Boolean(value) => Some(HashKey::Boolean(*value)),
+            Object::String(value) => Some(HashKey::String(value.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Object {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Object::Integer(value) => write!(f, "{}", value),
+            Object::Float(value) => write!(f, "{:?}", value),
+            Object::Boolean(value) => write!(f, "{}", value),
+            Object::String(value) => write!(f, "{}", value),
+            Object::Array(elements) => {
+                let elements: Vec<String> = elements.iter().map(Object::inspect).collect();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            Object::Hash(pairs) => {
+                // Keys are sorted so the same hash always prints the same way.
+                let mut pairs: Vec<(&HashKey, &Object)> = pairs.iter().collect();
+                pairs.sort_by_key(|(key, _)| *key);
+                let pairs: Vec<String> = pairs.iter().map(|(key, value)| format!("{}: {}", key, value.inspect())).collect();
+                write!(f, "{{{}}}", pairs.join(", "))
+            }
+            Object::Null => write!(f, "null"),
+            Object::ReturnValue(value) => write!(f, "{}", value),
+            Object::Error(message) => write!(f, "ERROR: {}", message),
+            Object::Function(function) => {
+                let parameters: Vec<&str> = function.parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+                write!(f, "fn({}) {}", parameters.join(", "), function.body.show())
+            }
+            Object::Builtin(builtin) => write!(f, "builtin function {}", builtin.name),
+            Object::Native(native) => write!(f, "builtin function {}", native.name),
+        }
+    }
+}
@@ -1,62 +1,125 @@
-use crate::lexer::token::Token;
-use crate::lexer::token::TokenType::*;
+use crate::lexer::token::{Span, Token};
+use crate::lexer::token::TokenType;
+use crate::lexer::token::TokenType::{
+    And, Assign, Asterisk, Bang, Colon, Comma, Dash, DocComment, Else, Eof, Equals, False, Float, ForwardSlash, Function,
+    GreaterEqual, GreaterThan, Ident, If, Illegal, Int, InvalidEscape, LesserEqual, LesserThan, Let, Lbrace, Lbracket, Lparen,
+    NotEqual, Or, Percent, Plus, Rbrace, Rbracket, Return, Rparen, Semicolon, True, UnterminatedComment, UnterminatedString,
+};
 use crate::token;
 
 pub mod token;
 
-pub struct Lexer {
-    input: Vec<u8>,
+/// Lexes UTF-8 source text one `char` at a time. Spans are byte offsets into the input, while
+/// columns count characters. Tokens borrow their literals from the input rather than copying them.
+///
+/// `//` line comments and nestable `/* */` block comments are skipped. `///` doc comments are
+/// skipped too unless `with_doc_comments` is used, in which case each line is emitted as a
+/// `DocComment` token.
+pub struct Lexer<'src> {
+    input: &'src str,
     current_position: usize,
     read_position: usize,
-    ch: u8,
+    ch: char,
+    line: usize,
+    column: usize,
+    doc_comments: bool,
 }
 
-impl Lexer {
-    pub fn new(input: String) -> Self {
+impl<'src> Lexer<'src> {
+    pub fn new(input: &'src str) -> Self {
         let mut lex = Self {
-            input: input.into_bytes(),
+            input,
             current_position: 0,
             read_position: 0,
-            ch: 0,
+            ch: '\0',
+            line: 1,
+            column: 0,
+            doc_comments: false,
         };
         lex.read_char();
         lex
     }
 
-    pub fn next_token(&mut self) -> Token {
-        self.skip_whitespace();
+    pub fn with_doc_comments(mut self) -> Self {
+        self.doc_comments = true;
+        self
+    }
+
+    pub fn next_token(&mut self) -> Token<'src> {
+        loop {
+            self.skip_whitespace();
+            let (start, line, column) = (self.current_position, self.line, self.column);
+            let tok = if self.ch == '/' && matches!(self.lookahead(), '/' | '*') {
+                self.read_comment()
+            } else {
+                Some(self.read_token())
+            };
+            if let Some(mut tok) = tok {
+                tok.span = Span::new(start, self.current_position, line, column);
+                return tok;
+            }
+        }
+    }
+
+    fn read_token(&mut self) -> Token<'src> {
         let tok: Token = match self.ch {
-            b'=' => {
-                if self.lookahead() == b'=' {
+            '=' => {
+                if self.lookahead() == '=' {
                     self.read_char();
                     token!(Equals, "==")
                 } else {
                     token!(Assign, "=")
                 }
             },
-            b'+' => token!(Plus, "+"),
-            b'-' => token!(Dash, "-"),
-            b'*' => token!(Asterisk, "*"),
-            b'(' => token!(Lparen, "("),
-            b')' => token!(Rparen, ")"),
-            b'{' => token!(Lbrace, "{"),
-            b'}' => token!(Rbrace, "}"),
-            b',' => token!(Comma, ","),
-            b';' => token!(Semicolon, ";"),
-            b'!' => {
-                if self.lookahead() == b'=' {
+            '+' => token!(Plus, "+"),
+            '-' => token!(Dash, "-"),
+            '*' => token!(Asterisk, "*"),
+            '(' => token!(Lparen, "("),
+            ')' => token!(Rparen, ")"),
+            '{' => token!(Lbrace, "{"),
+            '}' => token!(Rbrace, "}"),
+            '[' => token!(Lbracket, "["),
+            ']' => token!(Rbracket, "]"),
+            ',' => token!(Comma, ","),
+            ':' => token!(Colon, ":"),
+            ';' => token!(Semicolon, ";"),
+            '!' => {
+                if self.lookahead() == '=' {
                     self.read_char();
                     token!(NotEqual, "!=")
                 } else {
                     token!(Bang, "!")
                 }
             },
-            b'/' => token!(ForwardSlash, "/"),
-            b'<' => token!(LesserThan, "<"),
-            b'>' => token!(GreaterThan, ">"),
-            b'a'..=b'z' => {
+            '/' => token!(ForwardSlash, "/"),
+            '%' => token!(Percent, "%"),
+            '<' => {
+                if self.lookahead() == '=' {
+                    self.read_char();
+                    token!(LesserEqual, "<=")
+                } else {
+                    token!(LesserThan, "<")
+                }
+            },
+            '>' => {
+                if self.lookahead() == '=' {
+                    self.read_char();
+                    token!(GreaterEqual, ">=")
+                } else {
+                    token!(GreaterThan, ">")
+                }
+            },
+            '&' if self.lookahead() == '&' => {
+                self.read_char();
+                token!(And, "&&")
+            },
+            '|' if self.lookahead() == '|' => {
+                self.read_char();
+                token!(Or, "||")
+            },
+            ch if is_ident_start(ch) => {
                 let id = self.read_ident();
-                return match id.as_str() {
+                return match id {
                     "let" => token!(Let, "let"),
                     "fn" => token!(Function, "fn"),
                     "else" => token!(Else, "else"),
@@ -67,58 +130,250 @@ impl Lexer {
                     _ => token!(Ident, id),
                 }
             },
-            b'0'..=b'9' => return token!(Int, self.read_num()),
-            0 => token!(Eof, ""),
-            _ => token!(Illegal, ""),
+            '0'..='9' => return self.read_number(),
+            '"' => self.read_string(),
+            '\0' if self.at_eof() => token!(Eof, ""),
+            _ => token!(Illegal, &self.input[self.current_position..self.read_position]),
         };
         self.read_char();
         tok
     }
 
     fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = 0;
+        if self.ch == '\n' {
+            self.line += 1;
+            self.column = 1;
         } else {
-            self.ch = self.input[self.read_position];
+            self.column += 1;
         }
-
         self.current_position = self.read_position;
-        self.read_position += 1;
+        match self.input[self.read_position..].chars().next() {
+            Some(ch) => {
+                self.ch = ch;
+                self.read_position += ch.len_utf8();
+            }
+            None => self.ch = '\0',
+        }
+    }
+
+    fn at_eof(&self) -> bool {
+        self.current_position >= self.input.len()
     }
     
-    fn read_ident(&mut self) -> String {
+    fn read_ident(&mut self) -> &'src str {
         let prev_position = self.current_position;
-        while self.ch.is_ascii_alphabetic() || self.ch == b'_' {
+        while is_ident_continue(self.ch) {
             self.read_char();
         }
-        String::from_utf8_lossy(&self.input[prev_position..self.current_position]).to_string()
+        &self.input[prev_position..self.current_position]
     }
 
-    fn read_num(&mut self) -> String {
+    /// Reads a number literal, keeping its source text. Integers may use a `0x`, `0b` or `0o`
+    /// prefix, and decimal numbers with a fraction or exponent are floats. A prefixed literal
+    /// takes every letter and digit after the prefix so the parser can reject bad digits whole.
+    fn read_number(&mut self) -> Token<'src> {
         let prev_position = self.current_position;
-        while self.ch.is_ascii_digit() {
+        let mut token_type = Int;
+        if self.ch == '0' && matches!(self.lookahead(), 'x' | 'b' | 'o') {
+            self.read_char();
+            self.read_char();
+            while is_ident_continue(self.ch) {
+                self.read_char();
+            }
+        } else {
+            self.read_digits();
+            if self.ch == '.' && self.lookahead().is_ascii_digit() {
+                token_type = Float;
+                self.read_char();
+                self.read_digits();
+            }
+            let sign = matches!(self.lookahead(), '+' | '-');
+            let exponent_digit = if sign { self.lookahead_nth(1) } else { self.lookahead() };
+            if matches!(self.ch, 'e' | 'E') && exponent_digit.is_ascii_digit() {
+                token_type = Float;
+                self.read_char();
+                if sign {
+                    self.read_char();
+                }
+                self.read_digits();
+            }
+        }
+        token!(token_type, &self.input[prev_position..self.current_position])
+    }
+
+    fn read_digits(&mut self) {
+        while self.ch.is_ascii_digit() || self.ch == '_' {
             self.read_char();
         }
-        String::from_utf8_lossy(&self.input[prev_position..self.current_position]).to_string()
     }
     
+    /// Reads a comment starting at its first `/`, leaving the lexer just past it. Returns a token
+    /// only for doc comments being emitted and for unterminated block comments.
+    fn read_comment(&mut self) -> Option<Token<'src>> {
+        let start = self.current_position;
+        self.read_char();
+        if self.ch == '/' {
+            while self.ch != '\n' && !self.at_eof() {
+                self.read_char();
+            }
+            let text = &self.input[start..self.current_position];
+            let is_doc = text.starts_with("///") && !text.starts_with("////");
+            if !(is_doc && self.doc_comments) {
+                return None;
+            }
+            let doc = &text[3..];
+            return Some(token!(DocComment, doc.strip_prefix(' ').unwrap_or(doc).trim_end()));
+        }
+        self.read_char();
+        let mut depth = 1;
+        while depth > 0 {
+            if self.at_eof() {
+                return Some(token!(UnterminatedComment, &self.input[start..]));
+            }
+            if self.ch == '*' && self.lookahead() == '/' {
+                self.read_char();
+                depth -= 1;
+            } else if self.ch == '/' && self.lookahead() == '*' {
+                self.read_char();
+                depth += 1;
+            }
+            self.read_char();
+        }
+        None
+    }
+
+    /// Reads a string literal starting at its opening quote and leaves the lexer on the closing
+    /// one. The token's literal is the unescaped contents, or the offending text for
+    /// `UnterminatedString` and `InvalidEscape` tokens.
+    ///
+    /// The contents are only copied once an escape sequence means they differ from the source.
+    fn read_string(&mut self) -> Token<'src> {
+        let start = self.current_position;
+        let mut unescaped: Option<String> = None;
+        let mut invalid_escape = None;
+        loop {
+            self.read_char();
+            match self.ch {
+                '"' => break,
+                '\0' if self.at_eof() => return token!(UnterminatedString, &self.input[start..]),
+                '\\' => {
+                    let escape_start = self.current_position;
+                    let value = unescaped.get_or_insert_with(|| self.input[start + 1..escape_start].to_string());
+                    match self.read_escape() {
+                        Some(ch) => value.push(ch),
+                        None => {
+                            let end = self.read_position.min(self.input.len());
+                            invalid_escape.get_or_insert(&self.input[escape_start..end]);
+                        }
+                    }
+                }
+                ch => {
+                    if let Some(value) = &mut unescaped {
+                        value.push(ch);
+                    }
+                }
+            }
+        }
+        match (invalid_escape, unescaped) {
+            (Some(raw), _) => token!(InvalidEscape, raw),
+            (None, Some(value)) => token!(TokenType::String, value),
+            (None, None) => token!(TokenType::String, &self.input[start + 1..self.current_position]),
+        }
+    }
+
+    /// Reads the escape sequence following a backslash, leaving the lexer on its last character.
+    fn read_escape(&mut self) -> Option<char> {
+        self.read_char();
+        match self.ch {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            '"' => Some('"'),
+            '\\' => Some('\\'),
+            'u' if self.lookahead() == '{' => {
+                self.read_char();
+                let digits_start = self.read_position;
+                while self.lookahead().is_ascii_hexdigit() {
+                    self.read_char();
+                }
+                let digits = &self.input[digits_start..self.read_position];
+                if self.lookahead() != '}' {
+                    return None;
+                }
+                self.read_char();
+                if digits.is_empty() || digits.len() > 6 {
+                    return None;
+                }
+                u32::from_str_radix(digits, 16).ok().and_then(char::from_u32)
+            }
+            _ => None,
+        }
+    }
+
     fn skip_whitespace(&mut self) {
-        while self.ch.is_ascii_whitespace() {
+        while self.ch.is_whitespace() {
             self.read_char();
         }
     }
     
-    fn lookahead(&mut self) -> u8 {
-        return if self.read_position >= self.input.len() {
-            0
-        } else {
-            self.input[self.read_position]
-        }
+    fn lookahead(&self) -> char {
+        self.lookahead_nth(0)
+    }
+
+    /// The char `n` places after the one after the current char.
+    fn lookahead_nth(&self, n: usize) -> char {
+        self.input[self.read_position..].chars().nth(n).unwrap_or('\0')
+    }
+}
+
+/// Identifiers start with a letter or underscore, approximating Unicode's XID_Start.
+fn is_ident_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+/// Identifiers continue with letters, decimal digits, combining marks or underscores,
+/// approximating XID_Continue. Other numeric characters, such as `²`, are not part of an
+/// identifier.
+fn is_ident_continue(ch: char) -> bool {
+    is_ident_start(ch) || is_decimal_digit(ch) || is_combining_mark(ch)
+}
+
+/// The first code point of each run of ten decimal digits (category Nd), in order.
+const DIGIT_ZEROS: &[u32] = &[
+    0x30, 0x660, 0x6F0, 0x7C0, 0x966, 0x9E6, 0xA66, 0xAE6, 0xB66, 0xBE6, 0xC66, 0xCE6, 0xD66, 0xDE6, 0xE50, 0xED0,
+    0xF20, 0x1040, 0x1090, 0x17E0, 0x1810, 0x1946, 0x19D0, 0x1A80, 0x1A90, 0x1B50, 0x1BB0, 0x1C40, 0x1C50, 0xA620,
+    0xA8D0, 0xA900, 0xA9D0, 0xA9F0, 0xAA50, 0xABF0, 0xFF10, 0x104A0, 0x10D30, 0x11066, 0x110F0, 0x11136, 0x111D0,
+    0x112F0, 0x11450, 0x114D0, 0x11650, 0x116C0, 0x11730, 0x118E0, 0x11950, 0x11C50, 0x11D50, 0x11DA0, 0x16A60,
+    0x16AC0, 0x16B50, 0x1D7CE, 0x1D7D8, 0x1D7E2, 0x1D7EC, 0x1D7F6, 0x1E140, 0x1E2F0, 0x1E950, 0x1FBF0,
+];
+
+fn is_decimal_digit(ch: char) -> bool {
+    if ch.is_ascii() {
+        return ch.is_ascii_digit();
     }
+    let code = ch as u32;
+    match DIGIT_ZEROS.binary_search(&code) {
+        Ok(_) => true,
+        Err(0) => false,
+        Err(i) => code - DIGIT_ZEROS[i - 1] < 10,
+    }
+}
+
+/// The combining diacritical marks shared between scripts, such as the U+0301 accent that
+/// follows `e` in a decomposed `é`. Marks that belong to one script, such as Devanagari vowel
+/// signs, are mostly alphabetic already.
+fn is_combining_mark(ch: char) -> bool {
+    matches!(ch, '\u{0300}'..='\u{036F}'
+        | '\u{1AB0}'..='\u{1ABD}'
+        | '\u{1ABF}'..='\u{1ACE}'
+        | '\u{1DC0}'..='\u{1DFF}'
+        | '\u{20D0}'..='\u{20DC}'
+        | '\u{20E1}'
+        | '\u{20E5}'..='\u{20F0}'
+        | '\u{FE20}'..='\u{FE2F}')
 }
 
-impl Iterator for Lexer {
-    type Item = Token;
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Token<'src>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let tok = self.next_token();
@@ -134,11 +389,14 @@ impl Iterator for Lexer {
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
     use crate::lexer::token::TokenType::Assign;
     use crate::token;
     use super::*;
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_tokenize_simple() {
         let input = "=+(){},;";
         let expected = vec![
@@ -151,13 +409,14 @@ mod tests {
             token!(Comma, ","),
             token!(Semicolon, ";"),
         ];
-        let mut lex = Lexer::new(input.to_string());
+        let mut lex = Lexer::new(input);
         for tok in expected.iter() {
             assert_eq!(&lex.next_token(), tok)
         }
     }
 
     #[test]
+    #[allow(unused_variables, clippy::range_zip_with_len)]
     fn test_tokenize_complex() {
         let input = r#"let five = 5;
             let ten = 10;
@@ -167,7 +426,7 @@ mod tests {
             };
 
             let result = add(five,ten);
-            !-/*5;
+            !- / *5;
             5 < 10 > 5;
             "#;
         
@@ -221,11 +480,265 @@ mod tests {
             token!(Int, "5"),
             token!(Semicolon, ";"),
         ];
-        let mut lex = Lexer::new(input.to_string());
+        let mut lex = Lexer::new(input);
         for (tok, i) in expected.iter().zip(0..expected.len()) {
             let got = lex.next_token();
             //println!("expected: {:?}, got: {:?} at: {}", tok, got, i);
             assert_eq!(&got, tok)
         }
     }
+
+    #[test]
+    fn test_token_spans() {
+        let input = "let five = 5;\n  five == 10;";
+        let expected = [
+            Span::new(0, 3, 1, 1),
+            Span::new(4, 8, 1, 5),
+            Span::new(9, 10, 1, 10),
+            Span::new(11, 12, 1, 12),
+            Span::new(12, 13, 1, 13),
+            Span::new(16, 20, 2, 3),
+            Span::new(21, 23, 2, 8),
+            Span::new(24, 26, 2, 11),
+            Span::new(26, 27, 2, 13),
+            Span::new(27, 27, 2, 14),
+        ];
+        let mut lex = Lexer::new(input);
+        for span in expected.iter() {
+            assert_eq!(&lex.next_token().span, span)
+        }
+    }
+
+    #[test]
+    fn test_tokenize_strings() {
+        let input = r#""foobar" "foo bar" "" "a\nb\t\"c\"\\" "\u{48}\u{1F600}" "héllo""#;
+        let expected = [
+            token!(TokenType::String, "foobar"),
+            token!(TokenType::String, "foo bar"),
+            token!(TokenType::String, ""),
+            token!(TokenType::String, "a\nb\t\"c\"\\"),
+            token!(TokenType::String, "H\u{1F600}"),
+            token!(TokenType::String, "héllo"),
+            token!(Eof, ""),
+        ];
+        let mut lex = Lexer::new(input);
+        for tok in expected.iter() {
+            assert_eq!(&lex.next_token(), tok)
+        }
+    }
+
+    #[test]
+    fn test_skip_comments() {
+        let input = r#"// leading comment
+            let x = 1; // trailing comment
+            /* block */ x /* nested /* block */ still comment */ + 2;
+            /// doc comment
+            //// not a doc comment
+            x / 2 // ends at end of input"#;
+        let expected = [
+            token!(Let, "let"),
+            token!(Ident, "x"),
+            token!(Assign, "="),
+            token!(Int, "1"),
+            token!(Semicolon, ";"),
+            token!(Ident, "x"),
+            token!(Plus, "+"),
+            token!(Int, "2"),
+            token!(Semicolon, ";"),
+            token!(Ident, "x"),
+            token!(ForwardSlash, "/"),
+            token!(Int, "2"),
+            token!(Eof, ""),
+        ];
+        let mut lex = Lexer::new(input);
+        for tok in expected.iter() {
+            assert_eq!(&lex.next_token(), tok)
+        }
+    }
+
+    #[test]
+    fn test_emit_doc_comments() {
+        let input = "/// Adds two numbers.\n///\n///   Indented.\n//// plain\nlet";
+        let expected = [
+            (token!(DocComment, "Adds two numbers."), Span::new(0, 21, 1, 1)),
+            (token!(DocComment, ""), Span::new(22, 25, 2, 1)),
+            (token!(DocComment, "  Indented."), Span::new(26, 41, 3, 1)),
+            (token!(Let, "let"), Span::new(53, 56, 5, 1)),
+        ];
+        let mut lex = Lexer::new(input).with_doc_comments();
+        for (tok, span) in expected.iter() {
+            let got = lex.next_token();
+            assert_eq!(&got, tok);
+            assert_eq!(&got.span, span);
+        }
+    }
+
+    #[test]
+    fn test_unterminated_block_comment() {
+        let mut lex = Lexer::new("1 /* a /* b */");
+        assert_eq!(lex.next_token(), token!(Int, "1"));
+        let tok = lex.next_token();
+        assert_eq!(tok, token!(UnterminatedComment, "/* a /* b */"));
+        assert_eq!(tok.span, Span::new(2, 14, 1, 3));
+        assert_eq!(lex.next_token(), token!(Eof, ""));
+    }
+
+    #[test]
+    fn test_literals_borrow_from_source() {
+        let input = r#"let name = "plain" + "esc\n";"#;
+        let tokens: Vec<Token> = Lexer::new(input).collect();
+        let borrowed: Vec<bool> = tokens.iter().map(|tok| matches!(tok.literal, Cow::Borrowed(_))).collect();
+        assert_eq!(borrowed, [true, true, true, true, true, false, true]);
+        assert_eq!(tokens[5], token!(TokenType::String, "esc\n"));
+        let owned: Token<'static> = tokens[1].clone().into_owned();
+        assert_eq!(owned, token!(Ident, "name"));
+    }
+
+    #[test]
+    fn test_tokenize_operators() {
+        let input = "a <= b >= c < d > e && f || g % h & | =< [1] :";
+        let expected = [
+            token!(Ident, "a"),
+            token!(LesserEqual, "<="),
+            token!(Ident, "b"),
+            token!(GreaterEqual, ">="),
+            token!(Ident, "c"),
+            token!(LesserThan, "<"),
+            token!(Ident, "d"),
+            token!(GreaterThan, ">"),
+            token!(Ident, "e"),
+            token!(And, "&&"),
+            token!(Ident, "f"),
+            token!(Or, "||"),
+            token!(Ident, "g"),
+            token!(Percent, "%"),
+            token!(Ident, "h"),
+            token!(Illegal, "&"),
+            token!(Illegal, "|"),
+            token!(Assign, "="),
+            token!(LesserThan, "<"),
+            token!(Lbracket, "["),
+            token!(Int, "1"),
+            token!(Rbracket, "]"),
+            token!(Colon, ":"),
+            token!(Eof, ""),
+        ];
+        let mut lex = Lexer::new(input);
+        for tok in expected.iter() {
+            assert_eq!(&lex.next_token(), tok)
+        }
+    }
+
+    #[test]
+    fn test_tokenize_numbers() {
+        let input = "0 42 1_000_000 0xFF 0b1010 0o777 0xZZ 3.14 1e10 2.5E-3 6e+2 1_0.0_1 1.foo 7e";
+        let expected = [
+            token!(Int, "0"),
+            token!(Int, "42"),
+            token!(Int, "1_000_000"),
+            token!(Int, "0xFF"),
+            token!(Int, "0b1010"),
+            token!(Int, "0o777"),
+            token!(Int, "0xZZ"),
+            token!(Float, "3.14"),
+            token!(Float, "1e10"),
+            token!(Float, "2.5E-3"),
+            token!(Float, "6e+2"),
+            token!(Float, "1_0.0_1"),
+            token!(Int, "1"),
+            token!(Illegal, "."),
+            token!(Ident, "foo"),
+            token!(Int, "7"),
+            token!(Ident, "e"),
+            token!(Eof, ""),
+        ];
+        let mut lex = Lexer::new(input);
+        for tok in expected.iter() {
+            assert_eq!(&lex.next_token(), tok)
+        }
+    }
+
+    #[test]
+    fn test_tokenize_identifiers() {
+        let input = "myVar _private Point x2 snake_case_1 __ MAX_SIZE letter returned 2x";
+        let expected = [
+            token!(Ident, "myVar"),
+            token!(Ident, "_private"),
+            token!(Ident, "Point"),
+            token!(Ident, "x2"),
+            token!(Ident, "snake_case_1"),
+            token!(Ident, "__"),
+            token!(Ident, "MAX_SIZE"),
+            token!(Ident, "letter"),
+            token!(Ident, "returned"),
+            token!(Int, "2"),
+            token!(Ident, "x"),
+            token!(Eof, ""),
+        ];
+        let mut lex = Lexer::new(input);
+        for tok in expected.iter() {
+            assert_eq!(&lex.next_token(), tok)
+        }
+    }
+
+    #[test]
+    fn test_tokenize_unicode() {
+        let input = "let café = \"naïve 😀\";\nλ + 名前 § ß";
+        let expected = [
+            (token!(Let, "let"), Span::new(0, 3, 1, 1)),
+            (token!(Ident, "café"), Span::new(4, 9, 1, 5)),
+            (token!(Assign, "="), Span::new(10, 11, 1, 10)),
+            (token!(TokenType::String, "naïve 😀"), Span::new(12, 25, 1, 12)),
+            (token!(Semicolon, ";"), Span::new(25, 26, 1, 21)),
+            (token!(Ident, "λ"), Span::new(27, 29, 2, 1)),
+            (token!(Plus, "+"), Span::new(30, 31, 2, 3)),
+            (token!(Ident, "名前"), Span::new(32, 38, 2, 5)),
+            (token!(Illegal, "§"), Span::new(39, 41, 2, 8)),
+            (token!(Ident, "ß"), Span::new(42, 44, 2, 10)),
+            (token!(Eof, ""), Span::new(44, 44, 2, 11)),
+        ];
+        let mut lex = Lexer::new(input);
+        for (tok, span) in expected.iter() {
+            let got = lex.next_token();
+            assert_eq!(&got, tok);
+            assert_eq!(&got.span, span, "{:?}", tok);
+        }
+    }
+
+    #[test]
+    fn test_tokenize_unicode_identifiers() {
+        // A decomposed `é` is an `e` followed by a combining accent; superscripts are not digits.
+        let input = "cafe\u{301} x² x٣ n\u{303}o";
+        let expected = [
+            token!(Ident, "cafe\u{301}"),
+            token!(Ident, "x"),
+            token!(Illegal, "²"),
+            token!(Ident, "x٣"),
+            token!(Ident, "n\u{303}o"),
+            token!(Eof, ""),
+        ];
+        let mut lex = Lexer::new(input);
+        for tok in expected.iter() {
+            assert_eq!(&lex.next_token(), tok)
+        }
+    }
+
+    #[test]
+    fn test_tokenize_invalid_strings() {
+        let tests = [
+            (r#""abc"#, token!(UnterminatedString, "\"abc")),
+            (r#""a\qb""#, token!(InvalidEscape, "\\q")),
+            (r#""\u{110000}""#, token!(InvalidEscape, "\\u{110000}")),
+            (r#""\u{}""#, token!(InvalidEscape, "\\u{}")),
+            (r#""\u{41""#, token!(InvalidEscape, "\\u{41")),
+        ];
+        for (input, expected) in tests {
+            let mut lex = Lexer::new(input);
+            assert_eq!(lex.next_token(), expected, "{}", input);
+        }
+        let mut lex = Lexer::new(r#""a\qb" 1"#);
+        let tok = lex.next_token();
+        assert_eq!(tok.span, Span::new(0, 6, 1, 1));
+        assert_eq!(lex.next_token(), token!(Int, "1"));
+    }
 }
\ No newline at end of file
@@ -1,18 +1,76 @@
+use std::borrow::Cow;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 
-#[derive(Debug, PartialEq, Clone, Hash, Eq)]
-pub struct Token {
+/// A region of the source text: `start..end` are byte offsets, `line` and `column` are the
+/// 1-based position of `start`.
+#[derive(Debug, PartialEq, Clone, Copy, Default, Hash, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Self { start, end, line, column }
+    }
+
+    /// Returns a span running from the start of `self` to the end of `other`.
+    pub fn to(&self, other: Span) -> Span {
+        Span { end: other.end, ..*self }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Tokens compare by type and literal only; the span is positional metadata.
+///
+/// The literal borrows from the source wherever it appears there verbatim. Only string literals
+/// with escape sequences own their unescaped text.
+#[derive(Debug, Clone)]
+pub struct Token<'src> {
     pub token_type: TokenType,
-    pub literal: String,
+    pub literal: Cow<'src, str>,
+    pub span: Span,
+}
+
+impl<'src> Token<'src> {
+    pub fn new(token_type: TokenType, literal: impl Into<Cow<'src, str>>) -> Self {
+        Self { token_type, literal: literal.into(), span: Span::default() }
+    }
+
+    /// Detaches the token from the source, for keeping it beyond the source's lifetime.
+    pub fn into_owned(self) -> Token<'static> {
+        Token {
+            token_type: self.token_type,
+            literal: Cow::Owned(self.literal.into_owned()),
+            span: self.span,
+        }
+    }
 }
 
-impl Token {
-    pub fn new(token_type: TokenType, literal: String) -> Self {
-        Self { token_type, literal }
+impl PartialEq for Token<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.token_type == other.token_type && self.literal == other.literal
+    }
+}
+
+impl Eq for Token<'_> {}
+
+impl Hash for Token<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.token_type.hash(state);
+        self.literal.hash(state);
     }
 }
 
-impl Display for Token {
+impl Display for Token<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "Token({:?}, {})", self.token_type, self.literal)
     }
@@ -20,23 +78,27 @@ impl Display for Token {
 
 #[macro_export]
 macro_rules! token {
-    ($token_type:ident, $literal:expr) => {
-        Token::new($token_type, $literal.to_string())
+    ($token_type:path, $literal:expr) => {
+        Token::new($token_type, $literal)
     };
 }
 
-#[derive(Debug, PartialEq, Clone, Hash, Eq)]
+#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq)]
 pub enum TokenType {
     Let,
     Ident,
     Int,
+    Float,
     Assign,
     Plus,
     Lparen,
     Rparen,
     Lbrace,
     Rbrace,
+    Lbracket,
+    Rbracket,
     Comma,
+    Colon,
     Semicolon,
     Eof,
     Illegal,
@@ -45,15 +107,25 @@ pub enum TokenType {
     Dash,
     GreaterThan,
     LesserThan,
+    GreaterEqual,
+    LesserEqual,
     Equals,
     If,
     Else,
     Asterisk,
     ForwardSlash,
+    Percent,
     NotEqual,
+    And,
+    Or,
     False,
     True,
     Return,
+    String,
+    UnterminatedString,
+    InvalidEscape,
+    DocComment,
+    UnterminatedComment,
 }
 
 impl Display for TokenType {
@@ -62,13 +134,17 @@ impl Display for TokenType {
             TokenType::Let => "let",
             TokenType::Ident => "IDENT",
             TokenType::Int => "INT",
+            TokenType::Float => "FLOAT",
             TokenType::Assign => "=",
             TokenType::Plus => "+",
             TokenType::Lparen => "(",
             TokenType::Rparen => ")",
             TokenType::Lbrace => "{",
             TokenType::Rbrace => "}",
+            TokenType::Lbracket => "[",
+            TokenType::Rbracket => "]",
             TokenType::Comma => ",",
+            TokenType::Colon => ":",
             TokenType::Semicolon => ";",
             TokenType::Eof => "EOF",
             TokenType::Illegal => "ILLEGAL",
@@ -77,15 +153,25 @@ impl Display for TokenType {
             TokenType::Dash => "-",
             TokenType::GreaterThan => ">",
             TokenType::LesserThan => "<",
+            TokenType::GreaterEqual => ">=",
+            TokenType::LesserEqual => "<=",
             TokenType::Equals => "==",
             TokenType::If => "if",
             TokenType::Else => "else",
             TokenType::Asterisk => "*",
             TokenType::ForwardSlash => "/",
+            TokenType::Percent => "%",
             TokenType::NotEqual => "!=",
+            TokenType::And => "&&",
+            TokenType::Or => "||",
             TokenType::False => "false",
             TokenType::True => "true",
             TokenType::Return => "return",
+            TokenType::String => "STRING",
+            TokenType::UnterminatedString => "UNTERMINATED_STRING",
+            TokenType::InvalidEscape => "INVALID_ESCAPE",
+            TokenType::DocComment => "DOC_COMMENT",
+            TokenType::UnterminatedComment => "UNTERMINATED_COMMENT",
         };
         write!(f, "{}", token)
     }
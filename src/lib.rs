@@ -1,4 +1,6 @@
 pub mod lexer;
 pub mod repl;
 pub mod ast;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod eval;
+pub mod engine;
\ No newline at end of file
@@ -1,6 +1,131 @@
+use std::fs;
+use std::io;
+use std::process::ExitCode;
+use std::thread;
+
+use monkers::ast::show::Show;
+use monkers::ast::Program;
+use monkers::eval::environment::Environment;
+use monkers::eval::{eval_program, STACK_SIZE};
+use monkers::eval::object::Object;
+use monkers::lexer::Lexer;
+use monkers::parser::Parser;
 use monkers::repl::Repl;
 
-fn main() {
-    let repl = Repl::new();
-    repl.start();
+const USAGE: &str = "usage:
+    monkers [repl]              start an interactive session
+    monkers run <file>          evaluate a file
+    monkers lex <file>          print the tokens of a file
+    monkers parse [--debug] <file>
+                                print the parsed program, or its debug form
+    monkers -e <source>         evaluate <source> and print the result";
+
+// Parsing nested input and evaluating recursive programs both recurse on the Rust stack, so every
+// command runs on a thread with a stack big enough for them.
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = thread::Builder::new().stack_size(STACK_SIZE).spawn(move || command(&args));
+    match command {
+        // A panic has already reported itself on stderr.
+        Ok(command) => command.join().unwrap_or(ExitCode::FAILURE),
+        Err(error) => {
+            eprintln!("error: cannot start: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn command(args: &[String]) -> ExitCode {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] | ["repl"] => repl(),
+        ["run", path] => with_file(path, |source| run(path, source, false)),
+        ["lex", path] => with_file(path, lex),
+        ["parse", path] => with_file(path, |source| parse(path, source, false)),
+        ["parse", "--debug", path] => with_file(path, |source| parse(path, source, true)),
+        ["-e", source] => run("-e", source, true),
+        ["-h" | "--help" | "help"] => {
+            println!("{}", USAGE);
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn repl() -> ExitCode {
+    let mut repl = Repl::new(io::stdin().lock(), io::stdout());
+    match repl.start() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn with_file(path: &str, command: impl FnOnce(&str) -> ExitCode) -> ExitCode {
+    match fs::read_to_string(path) {
+        Ok(source) => command(&source),
+        Err(error) => {
+            eprintln!("error: cannot read {}: {}", path, error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn lex(source: &str) -> ExitCode {
+    for token in Lexer::new(source).with_doc_comments() {
+        println!("{} {}", token.span, token);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Parses `source`, reporting every parse error against `name` on stderr.
+fn parse_source(name: &str, source: &str) -> Option<Program> {
+    let mut parser = Parser::new(Lexer::new(source).with_doc_comments());
+    let (program, errors) = parser.parse_program();
+    if errors.is_empty() {
+        return Some(program);
+    }
+    eprintln!("{}: {} parse error{}", name, errors.len(), if errors.len() == 1 { "" } else { "s" });
+    for error in errors {
+        eprintln!("{}", error.render(source));
+    }
+    None
+}
+
+fn parse(name: &str, source: &str, debug: bool) -> ExitCode {
+    match parse_source(name, source) {
+        Some(program) if debug => println!("{:#?}", program),
+        Some(program) => {
+            for statement in &program.statements {
+                println!("{}", statement.show());
+            }
+        }
+        None => return ExitCode::FAILURE,
+    }
+    ExitCode::SUCCESS
+}
+
+/// Evaluates `source`, printing the result when `print_result` is set and it is not null.
+fn run(name: &str, source: &str, print_result: bool) -> ExitCode {
+    let Some(program) = parse_source(name, source) else {
+        return ExitCode::FAILURE;
+    };
+    match eval_program(&program, &mut Environment::new()) {
+        Object::Error(message) => {
+            eprintln!("{}: runtime error: {}", name, message);
+            ExitCode::FAILURE
+        }
+        Object::Null => ExitCode::SUCCESS,
+        result => {
+            if print_result {
+                println!("{}", result);
+            }
+            ExitCode::SUCCESS
+        }
+    }
 }
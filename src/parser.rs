@@ -1,57 +1,167 @@
 use std::collections::HashMap;
 
-use crate::ast::{Expression, ExpressionStatement, Identifier, IntegerLiteral, LetStatement, Program, ReturnStatement, Statement};
+use crate::ast::{BlockStatement, BooleanLiteral, Expression, ExpressionStatement, FloatLiteral, Identifier, IntegerLiteral, LetStatement, Program, ReturnStatement, Statement, StringLiteral};
 use crate::lexer::Lexer;
 use crate::lexer::token::{Token, TokenType};
-use crate::lexer::token::TokenType::{Bang, Dash, Ident, Illegal, Int};
+use crate::lexer::token::TokenType::{
+    And, Asterisk, Bang, Dash, Equals, False, Float, ForwardSlash, Function, GreaterEqual, GreaterThan, Ident, If, Illegal,
+    Int, InvalidEscape, LesserEqual, LesserThan, Lbrace, Lbracket, Lparen, NotEqual, Or, Percent, Plus, True, UnterminatedComment,
+    UnterminatedString,
+};
+use crate::parser::error::ParseError;
 use crate::parser::expression::{InfixParseFn, Precedence, PrefixParseFn};
 use crate::parser::expression::Precedence::Lowest;
 use crate::token;
 
+pub mod error;
 pub mod expression;
 
-pub struct Parser {
-    lexer: Lexer,
-    current_token: Token,
-    peek_token: Token,
+pub struct Parser<'src> {
+    lexer: Lexer<'src>,
+    current_token: Token<'src>,
+    peek_token: Token<'src>,
+    current_docs: Vec<String>,
+    peek_docs: Vec<String>,
     prefix_parse_fns: HashMap<TokenType, PrefixParseFn>,
     infix_parse_fns: HashMap<TokenType, InfixParseFn>,
+    errors: Vec<ParseError>,
 }
 
-impl Parser {
-    pub fn new(lexer: Lexer) -> Self {
+impl<'src> Parser<'src> {
+    pub fn new(lexer: Lexer<'src>) -> Self {
         let mut parser = Self {
             lexer,
             current_token: token!(Illegal, ""),
             peek_token: token!(Illegal, ""),
+            current_docs: Vec::new(),
+            peek_docs: Vec::new(),
             prefix_parse_fns: HashMap::new(),
             infix_parse_fns: HashMap::new(),
+            errors: Vec::new(),
         };
         parser.prefix_parse_fns.insert(Ident, parse_identifier);
         parser.prefix_parse_fns.insert(Int, parse_integer_literal);
+        parser.prefix_parse_fns.insert(Float, parse_float_literal);
         parser.prefix_parse_fns.insert(Bang, parse_prefix_expression);
         parser.prefix_parse_fns.insert(Dash, parse_prefix_expression);
+        parser.prefix_parse_fns.insert(True, parse_boolean);
+        parser.prefix_parse_fns.insert(False, parse_boolean);
+        parser.prefix_parse_fns.insert(Lparen, parse_grouped_expression);
+        parser.prefix_parse_fns.insert(If, parse_if_expression);
+        parser.prefix_parse_fns.insert(Function, parse_function_literal);
+        parser.prefix_parse_fns.insert(TokenType::String, parse_string_literal);
+        parser.prefix_parse_fns.insert(Lbracket, parse_array_literal);
+        parser.prefix_parse_fns.insert(Lbrace, parse_hash_literal);
+        parser.prefix_parse_fns.insert(UnterminatedString, parse_lexical_error);
+        parser.prefix_parse_fns.insert(InvalidEscape, parse_lexical_error);
+        parser.prefix_parse_fns.insert(UnterminatedComment, parse_lexical_error);
+        let operators = [
+            Plus, Dash, Asterisk, ForwardSlash, Percent, Equals, NotEqual, LesserThan, GreaterThan, LesserEqual,
+            GreaterEqual, And, Or,
+        ];
+        for operator in operators {
+            parser.infix_parse_fns.insert(operator, parse_infix_expression);
+        }
+        parser.infix_parse_fns.insert(Lparen, parse_call_expression);
+        parser.infix_parse_fns.insert(Lbracket, parse_index_expression);
         parser.next_token();
         parser.next_token();
         parser
     }
 
+    /// Advances by one token. Doc comment tokens are not parsed themselves but collected for the
+    /// token that follows them.
     pub fn next_token(&mut self) {
-        self.current_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token();
+        self.current_token = std::mem::replace(&mut self.peek_token, self.lexer.next_token());
+        self.current_docs = std::mem::take(&mut self.peek_docs);
+        while self.peek_token.token_type == TokenType::DocComment {
+            let doc = std::mem::replace(&mut self.peek_token, self.lexer.next_token());
+            self.peek_docs.push(doc.literal.into_owned());
+        }
+    }
+
+    /// Advances if the next token has type `expected`, otherwise fails without consuming it.
+    pub fn expect_peek(&mut self, expected: TokenType) -> Result<(), ParseError> {
+        if self.peek_token.token_type == expected {
+            self.next_token();
+            Ok(())
+        } else {
+            Err(ParseError::unexpected(&self.peek_token, vec![expected]))
+        }
+    }
+
+    pub fn peek_precedence(&self) -> Precedence {
+        Precedence::from(&self.peek_token.token_type)
+    }
+
+    pub fn current_precedence(&self) -> Precedence {
+        Precedence::from(&self.current_token.token_type)
     }
     
-    pub fn parse_program(&mut self) -> Result<Program, String> {
-        let mut program = Program { statements: vec![] };
-        while self.current_token.token_type != TokenType::Eof {
-            let statement = self.parse_statement()?;
-            program.statements.push(statement);
+    /// Parses the whole input, recovering from errors at statement boundaries. Returns every
+    /// statement that parsed successfully together with all the errors encountered.
+    pub fn parse_program(&mut self) -> (Program, Vec<ParseError>) {
+        let statements = self.parse_statements_until(TokenType::Eof);
+        (Program { statements }, std::mem::take(&mut self.errors))
+    }
+
+    /// Parses statements until the current token has type `end` or the input runs out,
+    /// recording errors and synchronizing after each one.
+    fn parse_statements_until(&mut self, end: TokenType) -> Vec<Statement> {
+        let mut statements = vec![];
+        while self.current_token.token_type != end && self.current_token.token_type != TokenType::Eof {
+            match self.parse_statement() {
+                Ok(statement) => {
+                    statements.push(statement);
+                    self.next_token();
+                }
+                Err(error) => {
+                    self.errors.push(error);
+                    self.synchronize(end);
+                }
+            }
+        }
+        statements
+    }
+
+    /// Parses a `{ ... }` block, starting with the current token on the opening brace and
+    /// leaving it on the closing one.
+    pub fn parse_block_statement(&mut self) -> Result<BlockStatement, ParseError> {
+        let start = self.current_token.span;
+        self.next_token();
+        let statements = self.parse_statements_until(TokenType::Rbrace);
+        if self.current_token.token_type != TokenType::Rbrace {
+            return Err(ParseError::unexpected(&self.current_token, vec![TokenType::Rbrace]));
+        }
+        Ok(BlockStatement {
+            statements,
+            span: start.to(self.current_token.span),
+        })
+    }
+
+    /// Skips tokens until the start of the next statement: just past a `;`, or at a `let`,
+    /// `return` or `}`. Consumes at least one token so parsing makes progress, unless the error
+    /// was at the `}` closing the block being parsed, which is left for the block to end on.
+    fn synchronize(&mut self, end: TokenType) {
+        if self.current_token.token_type == TokenType::Rbrace && end == TokenType::Rbrace {
+            return;
+        }
+        if self.current_token.token_type != TokenType::Semicolon {
             self.next_token();
         }
-        Ok(program)
+        loop {
+            match self.current_token.token_type {
+                TokenType::Eof | TokenType::Let | TokenType::Return | TokenType::Rbrace => return,
+                TokenType::Semicolon => {
+                    self.next_token();
+                    return;
+                }
+                _ => self.next_token(),
+            }
+        }
     }
     
-    pub fn parse_statement(&mut self) -> Result<Statement, String> {
+    pub fn parse_statement(&mut self) -> Result<Statement, ParseError> {
         match self.current_token.token_type {
             TokenType::Let => self.parse_let_statement(),
             TokenType::Return => self.parse_return_statement(),
@@ -59,83 +169,301 @@ impl Parser {
         }
     }
     
-    pub fn parse_let_statement(&mut self) -> Result<Statement, String> {
-        let token = self.current_token.clone();
-        self.next_token();
-        let identifier = if let Token { token_type: TokenType::Ident, literal } = self.current_token.clone() {
-            Identifier(literal.clone())
-        } else {
-            return Err(format!("Expected Ident, got {:?}", self.current_token));
+    pub fn parse_let_statement(&mut self) -> Result<Statement, ParseError> {
+        let start = self.current_token.span;
+        let doc = if self.current_docs.is_empty() { None } else { Some(self.current_docs.join("\n")) };
+        self.expect_peek(TokenType::Ident)?;
+        let identifier = Identifier {
+            name: self.current_token.literal.to_string(),
+            span: self.current_token.span,
         };
-        self.next_token();
-        if self.current_token.token_type != TokenType::Assign {
-            return Err(format!("Expected Assign, got {:?}", self.current_token));
-        }
+        self.expect_peek(TokenType::Assign)?;
         self.next_token();
         let expression = self.parse_expression(Lowest)?;
-        let statement = Statement::Let(LetStatement {
-            token,
-            name: identifier.clone(),
+        self.expect_peek(TokenType::Semicolon)?;
+        Ok(Statement::Let(LetStatement {
+            name: identifier,
             value: expression,
-        });
-        if self.peek_token.token_type == TokenType::Semicolon {
-            self.next_token();
-        } else {
-            return Err(format!("Expected Semicolon, got {:?}", self.peek_token));
-        }
-        Ok(statement)
+            doc,
+            span: start.to(self.current_token.span),
+        }))
     }
     
-    pub fn parse_return_statement(&mut self) -> Result<Statement, String> {
-        let token = self.current_token.clone();
+    pub fn parse_return_statement(&mut self) -> Result<Statement, ParseError> {
+        let start = self.current_token.span;
         self.next_token();
         let return_value = self.parse_expression(Lowest)?;
-        let statement = Statement::Return(ReturnStatement {
-            token,
-            return_value,
-        });
         if self.peek_token.token_type == TokenType::Semicolon {
             self.next_token();
         }
-        Ok(statement)
+        Ok(Statement::Return(ReturnStatement {
+            return_value,
+            span: start.to(self.current_token.span),
+        }))
     }
     
-    pub fn parse_expression(&mut self, precedence: Precedence) -> Result<Expression, String> {
-        let prefix = self.prefix_parse_fns.get(&self.current_token.token_type);
-        if prefix.is_none() {
-            return Err(format!("No prefix parse function for {:?}", self.current_token));
+    pub fn parse_expression(&mut self, precedence: Precedence) -> Result<Expression, ParseError> {
+        let prefix = match self.prefix_parse_fns.get(&self.current_token.token_type) {
+            Some(prefix) => *prefix,
+            None if self.current_token.token_type == TokenType::Eof => {
+                return Err(ParseError::unexpected(&self.current_token, vec![]));
+            }
+            None => return Err(ParseError::MissingPrefixParseFn { token: self.current_token.clone().into_owned() }),
+        };
+        let mut left_expression = prefix(self)?;
+        while self.peek_token.token_type != TokenType::Semicolon && precedence < self.peek_precedence() {
+            let infix = match self.infix_parse_fns.get(&self.peek_token.token_type) {
+                Some(infix) => *infix,
+                None => return Ok(left_expression),
+            };
+            self.next_token();
+            left_expression = infix(self, left_expression)?;
         }
-        let left_expression = prefix.unwrap()(self)?;
         Ok(left_expression)
     }
     
-    pub fn parse_expression_statement(&mut self) -> Result<Statement, String> {
+    /// Parses comma-separated expressions up to and including a closing `end` token, starting
+    /// with the current token on the opening delimiter.
+    pub fn parse_expression_list(&mut self, end: TokenType) -> Result<Vec<Expression>, ParseError> {
+        let mut list = vec![];
+        if self.peek_token.token_type == end {
+            self.next_token();
+            return Ok(list);
+        }
+        self.next_token();
+        list.push(self.parse_expression(Lowest)?);
+        while self.peek_token.token_type == TokenType::Comma {
+            self.next_token();
+            self.next_token();
+            list.push(self.parse_expression(Lowest)?);
+        }
+        if self.peek_token.token_type != end {
+            return Err(ParseError::unexpected(&self.peek_token, vec![TokenType::Comma, end]));
+        }
+        self.next_token();
+        Ok(list)
+    }
+
+    fn parse_function_parameters(&mut self) -> Result<Vec<Identifier>, ParseError> {
+        let mut parameters = vec![];
+        if self.peek_token.token_type == TokenType::Rparen {
+            self.next_token();
+            return Ok(parameters);
+        }
+        loop {
+            self.expect_peek(TokenType::Ident)?;
+            parameters.push(Identifier {
+                name: self.current_token.literal.to_string(),
+                span: self.current_token.span,
+            });
+            match self.peek_token.token_type {
+                TokenType::Comma => self.next_token(),
+                TokenType::Rparen => {
+                    self.next_token();
+                    return Ok(parameters);
+                }
+                _ => return Err(ParseError::unexpected(&self.peek_token, vec![TokenType::Comma, TokenType::Rparen])),
+            }
+        }
+    }
+
+    pub fn parse_expression_statement(&mut self) -> Result<Statement, ParseError> {
         let expression = self.parse_expression(Lowest)?;
-        let statement = Statement::Expression(ExpressionStatement {
-            token: self.current_token.clone(),
-            expression,
-        });
         if self.peek_token.token_type == TokenType::Semicolon {
             self.next_token();
         }
-        Ok(statement)
+        Ok(Statement::Expression(ExpressionStatement {
+            span: expression.span().to(self.current_token.span),
+            expression,
+        }))
     }
 }
 
-pub fn parse_identifier(parser: &mut Parser) -> Result<Expression, String> {
-    Ok(Expression::Id(Identifier(parser.current_token.literal.clone())))
+pub fn parse_identifier(parser: &mut Parser) -> Result<Expression, ParseError> {
+    Ok(Expression::Id(Identifier {
+        name: parser.current_token.literal.to_string(),
+        span: parser.current_token.span,
+    }))
+}
+
+/// Parses decimal, `0x` hexadecimal, `0b` binary and `0o` octal literals, any of which may use
+/// `_` separators. Literals that do not fit in an `i64` are errors.
+pub fn parse_integer_literal(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let literal = parser.current_token.literal.replace('_', "");
+    let (digits, radix) = match literal.get(..2) {
+        Some("0x") => (&literal[2..], 16),
+        Some("0b") => (&literal[2..], 2),
+        Some("0o") => (&literal[2..], 8),
+        _ => (literal.as_str(), 10),
+    };
+    // `from_str_radix` would accept a sign of its own, as in `0x-1`.
+    let val = if digits.starts_with(['+', '-']) { None } else { i64::from_str_radix(digits, radix).ok() };
+    let val = val.ok_or_else(|| ParseError::InvalidIntegerLiteral {
+        token: parser.current_token.clone().into_owned(),
+    })?;
+    Ok(Expression::Integer(IntegerLiteral {
+        value: val,
+        span: parser.current_token.span,
+    }))
+}
+
+pub fn parse_float_literal(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let val = parser.current_token.literal.replace('_', "").parse::<f64>().ok().filter(|val| val.is_finite());
+    let val = val.ok_or_else(|| ParseError::InvalidFloatLiteral {
+        token: parser.current_token.clone().into_owned(),
+    })?;
+    Ok(Expression::Float(FloatLiteral {
+        value: val,
+        span: parser.current_token.span,
+    }))
 }
 
-pub fn parse_integer_literal(parser: &mut Parser) -> Result<Expression, String> {
-    let val = parser.current_token.literal.parse::<i64>().unwrap();
-    Ok(Expression::Integer(IntegerLiteral(val)))
+pub fn parse_boolean(parser: &mut Parser) -> Result<Expression, ParseError> {
+    Ok(Expression::Boolean(BooleanLiteral {
+        value: parser.current_token.token_type == True,
+        span: parser.current_token.span,
+    }))
 }
 
-pub fn parse_prefix_expression(parser: &mut Parser) -> Result<Expression, String> {
-    let operator = parser.current_token.literal.clone();
+pub fn parse_string_literal(parser: &mut Parser) -> Result<Expression, ParseError> {
+    Ok(Expression::StringLiteral(StringLiteral {
+        value: parser.current_token.literal.to_string(),
+        span: parser.current_token.span,
+    }))
+}
+
+/// Reports the tokens the lexer produces for malformed strings and comments.
+pub fn parse_lexical_error(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let token = parser.current_token.clone().into_owned();
+    Err(match token.token_type {
+        UnterminatedString => ParseError::UnterminatedString { token },
+        UnterminatedComment => ParseError::UnterminatedComment { token },
+        _ => ParseError::InvalidEscape { token },
+    })
+}
+
+/// There is no node for parentheses, so the inner expression takes their span instead.
+pub fn parse_grouped_expression(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let start = parser.current_token.span;
+    parser.next_token();
+    let mut expression = parser.parse_expression(Lowest)?;
+    parser.expect_peek(TokenType::Rparen)?;
+    *expression.span_mut() = start.to(parser.current_token.span);
+    Ok(expression)
+}
+
+pub fn parse_if_expression(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let start = parser.current_token.span;
+    parser.expect_peek(TokenType::Lparen)?;
+    parser.next_token();
+    let condition = parser.parse_expression(Lowest)?;
+    parser.expect_peek(TokenType::Rparen)?;
+    parser.expect_peek(TokenType::Lbrace)?;
+    let consequence = parser.parse_block_statement()?;
+    let alternative = if parser.peek_token.token_type == TokenType::Else {
+        parser.next_token();
+        parser.expect_peek(TokenType::Lbrace)?;
+        Some(parser.parse_block_statement()?)
+    } else {
+        None
+    };
+    Ok(Expression::If {
+        condition: Box::new(condition),
+        consequence,
+        alternative,
+        span: start.to(parser.current_token.span),
+    })
+}
+
+pub fn parse_function_literal(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let start = parser.current_token.span;
+    parser.expect_peek(TokenType::Lparen)?;
+    let parameters = parser.parse_function_parameters()?;
+    parser.expect_peek(TokenType::Lbrace)?;
+    let body = parser.parse_block_statement()?;
+    Ok(Expression::FunctionLiteral {
+        parameters,
+        body,
+        span: start.to(parser.current_token.span),
+    })
+}
+
+pub fn parse_call_expression(parser: &mut Parser, function: Expression) -> Result<Expression, ParseError> {
+    let arguments = parser.parse_expression_list(TokenType::Rparen)?;
+    Ok(Expression::Call {
+        span: function.span().to(parser.current_token.span),
+        function: Box::new(function),
+        arguments,
+    })
+}
+
+pub fn parse_array_literal(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let start = parser.current_token.span;
+    let elements = parser.parse_expression_list(TokenType::Rbracket)?;
+    Ok(Expression::Array {
+        elements,
+        span: start.to(parser.current_token.span),
+    })
+}
+
+/// Parses a `{key: value, ...}` literal. Blocks are only parsed where a statement list is
+/// expected, so a `{` in expression position always starts a hash.
+pub fn parse_hash_literal(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let start = parser.current_token.span;
+    let mut pairs = vec![];
+    if parser.peek_token.token_type != TokenType::Rbrace {
+        loop {
+            parser.next_token();
+            let key = parser.parse_expression(Lowest)?;
+            parser.expect_peek(TokenType::Colon)?;
+            parser.next_token();
+            let value = parser.parse_expression(Lowest)?;
+            pairs.push((key, value));
+            match parser.peek_token.token_type {
+                TokenType::Comma => parser.next_token(),
+                TokenType::Rbrace => break,
+                _ => return Err(ParseError::unexpected(&parser.peek_token, vec![TokenType::Comma, TokenType::Rbrace])),
+            }
+        }
+    }
+    parser.next_token();
+    Ok(Expression::Hash {
+        pairs,
+        span: start.to(parser.current_token.span),
+    })
+}
+
+pub fn parse_index_expression(parser: &mut Parser, left: Expression) -> Result<Expression, ParseError> {
+    parser.next_token();
+    let index = parser.parse_expression(Lowest)?;
+    parser.expect_peek(TokenType::Rbracket)?;
+    Ok(Expression::Index {
+        span: left.span().to(parser.current_token.span),
+        left: Box::new(left),
+        index: Box::new(index),
+    })
+}
+
+pub fn parse_prefix_expression(parser: &mut Parser) -> Result<Expression, ParseError> {
+    let start = parser.current_token.span;
+    let operator = parser.current_token.literal.to_string();
     parser.next_token();
     let right = parser.parse_expression(Precedence::Prefix)?;
     Ok(Expression::Prefix {
+        operator,
+        span: start.to(right.span()),
+        right: Box::new(right),
+    })
+}
+
+pub fn parse_infix_expression(parser: &mut Parser, left: Expression) -> Result<Expression, ParseError> {
+    let operator = parser.current_token.literal.to_string();
+    let precedence = parser.current_precedence();
+    parser.next_token();
+    let right = parser.parse_expression(precedence)?;
+    Ok(Expression::Infix {
+        span: left.span().to(right.span()),
+        left: Box::new(left),
         operator,
         right: Box::new(right),
     })
@@ -143,123 +471,182 @@ pub fn parse_prefix_expression(parser: &mut Parser) -> Result<Expression, String
 
 #[cfg(test)]
 mod tests {
-    use crate::ast::{Expression, ExpressionStatement, Identifier, IntegerLiteral, LetStatement, ReturnStatement, Statement};
+    use crate::ast::{Expression, ExpressionStatement, Identifier, IntegerLiteral, LetStatement, Program, ReturnStatement, Statement};
+    use crate::ast::show::Show;
     use crate::lexer::Lexer;
-    use crate::lexer::token::Token;
-    use crate::lexer::token::TokenType::{Ident, Int, Let, Return};
+    use crate::lexer::token::{Span, TokenType};
+    use crate::parser::error::ParseError;
     use crate::parser::Parser;
-    use crate::token;
+
+    fn parse(input: &str) -> Program {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let (program, errors) = parser.parse_program();
+        assert!(errors.is_empty(), "Unexpected parse errors for {:?}: {:?}", input, errors);
+        program
+    }
+
+    fn parse_errors(input: &str) -> Vec<ParseError> {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        parser.parse_program().1
+    }
+
+    fn identifier(name: &str, span: Span) -> Identifier {
+        Identifier { name: name.to_string(), span }
+    }
+
+    fn integer(value: i64, span: Span) -> Expression {
+        Expression::Integer(IntegerLiteral { value, span })
+    }
 
     #[test]
-    fn test_let_statements() -> Result<(), String> {
+    fn test_let_statements() {
         let input = r#"
         let x = 5;
         let y = 10;
         let foobar = 838383;
         "#;
         
-        let lexer = Lexer::new(input.to_string());
-        let mut parser = Parser::new(lexer);
-        let program = parser.parse_program()?;
+        let program = parse(input);
         assert_eq!(program.statements.len(), 3);
         let expected_statements: Vec<Statement> = vec![
             Statement::Let(LetStatement {
-                token: token!(Let, "let"),
-                name: Identifier("x".to_string()),
-                value: Expression::Integer(IntegerLiteral(5)),
+                name: identifier("x", Span::new(13, 14, 2, 13)),
+                value: integer(5, Span::new(17, 18, 2, 17)),
+                doc: None,
+                span: Span::new(9, 19, 2, 9),
             }),
             Statement::Let(LetStatement {
-                token: token!(Let, "let"),
-                name: Identifier("y".to_string()),
-                value: Expression::Integer(IntegerLiteral(10)),
+                name: identifier("y", Span::new(32, 33, 3, 13)),
+                value: integer(10, Span::new(36, 38, 3, 17)),
+                doc: None,
+                span: Span::new(28, 39, 3, 9),
             }),
             Statement::Let(LetStatement {
-                token: token!(Let, "let"),
-                name: Identifier("foobar".to_string()),
-                value: Expression::Integer(IntegerLiteral(838383)),
+                name: identifier("foobar", Span::new(52, 58, 4, 13)),
+                value: integer(838383, Span::new(61, 67, 4, 22)),
+                doc: None,
+                span: Span::new(48, 68, 4, 9),
             }),
         ];
         
         for (i, statement) in program.statements.iter().enumerate() {
             assert_eq!(statement, &expected_statements[i]);
         }
-        
-        Ok(())
     }
     
     #[test]
-    fn test_return_statements() -> Result<(), String> {
+    fn test_return_statements() {
         let input = r#"
         return 5;
         return 10;
         return 993322;
         "#;
         
-        let lexer = Lexer::new(input.to_string());
-        let mut parser = Parser::new(lexer);
-        let program = parser.parse_program()?;
+        let program = parse(input);
         assert_eq!(program.statements.len(), 3);
         let expected_statements: Vec<Statement> = vec![
-            Statement::Return(ReturnStatement { token: token!(Return, "return"), return_value: Expression::Integer(IntegerLiteral(5)) }),
-            Statement::Return(ReturnStatement { token: token!(Return, "return"), return_value: Expression::Integer(IntegerLiteral(10)) }),
-            Statement::Return(ReturnStatement { token: token!(Return, "return"), return_value: Expression::Integer(IntegerLiteral(993322)) })
+            Statement::Return(ReturnStatement { return_value: integer(5, Span::new(16, 17, 2, 16)), span: Span::new(9, 18, 2, 9) }),
+            Statement::Return(ReturnStatement { return_value: integer(10, Span::new(34, 36, 3, 16)), span: Span::new(27, 37, 3, 9) }),
+            Statement::Return(ReturnStatement { return_value: integer(993322, Span::new(53, 59, 4, 16)), span: Span::new(46, 60, 4, 9) }),
         ];
         
         for (i, statement) in program.statements.iter().enumerate() {
             assert_eq!(statement, &expected_statements[i]);
         }
-        
-        Ok(())
     }
     
     #[test]
-    fn test_identifier_expression() -> Result<(), String> {
+    fn test_identifier_expression() {
         let input = "foobar;";
-        let lexer = Lexer::new(input.to_string());
-        let mut parser = Parser::new(lexer);
-        let program = parser.parse_program()?;
+        let program = parse(input);
         assert_eq!(program.statements.len(), 1);
-        let expected_statement = Statement::Expression(ExpressionStatement { token: token!(Ident, "foobar"), expression: Expression::Id(Identifier("foobar".to_string())) });
+        let expected_statement = Statement::Expression(ExpressionStatement {
+            expression: Expression::Id(identifier("foobar", Span::new(0, 6, 1, 1))),
+            span: Span::new(0, 7, 1, 1),
+        });
         assert_eq!(program.statements[0], expected_statement);
-        Ok(())
     }
     
     #[test]
-    fn test_integer_expression() -> Result<(), String> {
+    fn test_integer_expression() {
         let input = "5;";
-        let lexer = Lexer::new(input.to_string());
-        let mut parser = Parser::new(lexer);
-        let program = parser.parse_program()?;
+        let program = parse(input);
         assert_eq!(program.statements.len(), 1);
-        let expected_statement = Statement::Expression(ExpressionStatement { token: token!(Int, "5"), expression: Expression::Integer(IntegerLiteral(5)) });
+        let expected_statement = Statement::Expression(ExpressionStatement {
+            expression: integer(5, Span::new(0, 1, 1, 1)),
+            span: Span::new(0, 2, 1, 1),
+        });
         assert_eq!(program.statements[0], expected_statement);
-        Ok(())
     }
     
     #[test]
-    fn test_prefix_expression() -> Result<(), String> {
+    fn test_numeric_literals() {
+        let tests = [
+            ("1_000_000", Some(1_000_000)),
+            ("0xff", Some(255)),
+            ("0b1010", Some(10)),
+            ("0o17", Some(15)),
+            ("0x7FFF_FFFF_FFFF_FFFF", Some(i64::MAX)),
+            ("9223372036854775807", Some(i64::MAX)),
+            ("1.5", None),
+        ];
+        for (input, expected) in tests {
+            let program = parse(input);
+            match (&program.statements[0], expected) {
+                (Statement::Expression(ExpressionStatement { expression: Expression::Integer(int), .. }), Some(value)) => {
+                    assert_eq!(int.value, value, "{}", input);
+                }
+                (Statement::Expression(ExpressionStatement { expression: Expression::Float(float), .. }), None) => {
+                    assert_eq!(float.value, 1.5);
+                }
+                (statement, _) => panic!("Unexpected statement for {}: {:?}", input, statement),
+            }
+        }
+        assert_eq!(parse("2.5e3 + 1e-2 * 3.0").show(), "(2500.0 + (0.01 * 3.0))");
+    }
+
+    #[test]
+    fn test_numeric_literal_errors() {
+        let tests = [
+            ("99999999999999999999;", "1:1: invalid integer literal `99999999999999999999`"),
+            ("0x8000000000000000;", "1:1: invalid integer literal `0x8000000000000000`"),
+            ("0b102;", "1:1: invalid integer literal `0b102`"),
+            ("0x;", "1:1: invalid integer literal `0x`"),
+            ("1e999;", "1:1: invalid float literal `1e999`"),
+        ];
+        for (input, expected) in tests {
+            let errors = parse_errors(input);
+            assert_eq!(errors.len(), 1, "{}", input);
+            assert_eq!(errors[0].to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_prefix_expression() {
         let input = "!5;-15;";
-        let lexer = Lexer::new(input.to_string());
-        let mut parser = Parser::new(lexer);
-        let program = parser.parse_program()?;
+        let program = parse(input);
         assert_eq!(program.statements.len(), 2);
         let expected_statements: Vec<Statement> = vec![
             Statement::Expression(
                 ExpressionStatement { 
-                    token: token!(Int, "5"),
                     expression: Expression::Prefix { 
                         operator: "!".to_string(), 
-                        right: Box::new(Expression::Integer(IntegerLiteral(5))) 
-                    } 
+                        right: Box::new(integer(5, Span::new(1, 2, 1, 2))),
+                        span: Span::new(0, 2, 1, 1),
+                    },
+                    span: Span::new(0, 3, 1, 1),
                 }
             ),
             Statement::Expression(
                 ExpressionStatement { 
-                    token: token!(Int, "15"), 
                     expression: Expression::Prefix { 
                         operator: "-".to_string(), 
-                        right: Box::new(Expression::Integer(IntegerLiteral(15))) 
-                    }
+                        right: Box::new(integer(15, Span::new(4, 6, 1, 5))),
+                        span: Span::new(3, 6, 1, 4),
+                    },
+                    span: Span::new(3, 7, 1, 4),
                 }
             ),
         ];
@@ -267,8 +654,446 @@ mod tests {
         for (i, statement) in program.statements.iter().enumerate() {
             assert_eq!(statement, &expected_statements[i]);
         }
-        
-        Ok(())
+    }
+    
+    #[test]
+    fn test_infix_expression() {
+        let input = "5 + 5;5 - 5;5 * 5;5 / 5;5 % 5;5 > 5;5 < 5;5 >= 5;5 <= 5;5 == 5;5 != 5;5 && 5;5 || 5;";
+        let program = parse(input);
+        let operators = ["+", "-", "*", "/", "%", ">", "<", ">=", "<=", "==", "!=", "&&", "||"];
+        assert_eq!(program.statements.len(), operators.len());
+        // Each statement is `5 <operator> 5;`, following straight on from the one before.
+        let mut start = 0;
+        for (statement, operator) in program.statements.iter().zip(operators) {
+            let end = start + operator.len() + 4;
+            let expected_expression = Expression::Infix {
+                left: Box::new(integer(5, Span::new(start, start + 1, 1, start + 1))),
+                operator: operator.to_string(),
+                right: Box::new(integer(5, Span::new(end - 1, end, 1, end))),
+                span: Span::new(start, end, 1, start + 1),
+            };
+            match statement {
+                Statement::Expression(ExpressionStatement { expression, .. }) => assert_eq!(expression, &expected_expression),
+                _ => panic!("Expected expression statement, got {:?}", statement),
+            }
+            start = end + 1;
+        }
+    }
+    
+    #[test]
+    fn test_operator_precedence() {
+        let tests = [
+            ("-a * b", "((-a) * b)"),
+            ("!-a", "(!(-a))"),
+            ("a + b + c", "((a + b) + c)"),
+            ("a + b - c", "((a + b) - c)"),
+            ("a * b * c", "((a * b) * c)"),
+            ("a * b / c", "((a * b) / c)"),
+            ("a + b / c", "(a + (b / c))"),
+            ("a + b * c + d / e - f", "(((a + (b * c)) + (d / e)) - f)"),
+            ("3 + 4; -5 * 5", "(3 + 4);((-5) * 5)"),
+            ("5 > 4 == 3 < 4", "((5 > 4) == (3 < 4))"),
+            ("5 < 4 != 3 > 4", "((5 < 4) != (3 > 4))"),
+            ("3 + 4 * 5 == 3 * 1 + 4 * 5", "((3 + (4 * 5)) == ((3 * 1) + (4 * 5)))"),
+            ("5 + 5 * 2", "(5 + (5 * 2))"),
+            ("true", "true"),
+            ("3 > 5 == false", "((3 > 5) == false)"),
+            ("3 < 5 == true", "((3 < 5) == true)"),
+            ("!true", "(!true)"),
+            ("1 + (2 + 3) + 4", "((1 + (2 + 3)) + 4)"),
+            ("(5 + 5) * 2", "((5 + 5) * 2)"),
+            ("2 / (5 + 5)", "(2 / (5 + 5))"),
+            ("-(5 + 5)", "(-(5 + 5))"),
+            ("!(true == true)", "(!(true == true))"),
+            ("a + b % c * d", "(a + ((b % c) * d))"),
+            ("a <= b == c >= d", "((a <= b) == (c >= d))"),
+            ("a || b && c", "(a || (b && c))"),
+            ("a && b || c && d", "((a && b) || (c && d))"),
+            ("a == b && c != d || !e", "(((a == b) && (c != d)) || (!e))"),
+            ("a < b && b < c", "((a < b) && (b < c))"),
+            ("a * [1, 2, 3, 4][b * c] * d", "((a * ([1, 2, 3, 4][(b * c)])) * d)"),
+            ("add(a * b[2], b[1], 2 * [1, 2][1])", "add((a * (b[2])), (b[1]), (2 * ([1, 2][1])))"),
+            ("-a[0]", "(-(a[0]))"),
+            ("f(x)[0][1]", "((f(x)[0])[1])"),
+            ("a + add(b * c) + d", "((a + add((b * c))) + d)"),
+            ("add(a, b, 1, 2 * 3, 4 + 5, add(6, 7 * 8))", "add(a, b, 1, (2 * 3), (4 + 5), add(6, (7 * 8)))"),
+            ("add(a + b + c * d / f + g)", "add((((a + b) + ((c * d) / f)) + g))"),
+            ("-add(1)", "(-add(1))"),
+            ("fn(x) { x }(5)", "fn(x) { x }(5)"),
+        ];
+        for (input, expected) in tests {
+            let program = parse(input);
+            assert_eq!(program.show(), expected);
+        }
+    }
+    
+    #[test]
+    fn test_spans() {
+        let input = "let x = 5;\nreturn -x;\n1 + 2 * 3";
+        let program = parse(input);
+        let spans: Vec<Span> = program.statements.iter().map(|statement| statement.span()).collect();
+        assert_eq!(spans, vec![Span::new(0, 10, 1, 1), Span::new(11, 21, 2, 1), Span::new(22, 31, 3, 1)]);
+        match &program.statements[1] {
+            Statement::Return(ReturnStatement { return_value, .. }) => assert_eq!(return_value.span(), Span::new(18, 20, 2, 8)),
+            statement => panic!("Expected return statement, got {:?}", statement),
+        }
+        match &program.statements[2] {
+            Statement::Expression(ExpressionStatement { expression: Expression::Infix { right, .. }, .. }) => {
+                assert_eq!(right.span(), Span::new(26, 31, 3, 5));
+            }
+            statement => panic!("Expected infix expression, got {:?}", statement),
+        }
+        let grouped = parse("(1 + 2) * 3;\n-(5 + 5)");
+        let spans: Vec<Span> = grouped.statements.iter().map(|statement| statement.span()).collect();
+        assert_eq!(spans, vec![Span::new(0, 12, 1, 1), Span::new(13, 21, 2, 1)]);
+        match &grouped.statements[1] {
+            Statement::Expression(ExpressionStatement { expression: Expression::Prefix { right, .. }, .. }) => {
+                assert_eq!(right.span(), Span::new(14, 21, 2, 2));
+            }
+            statement => panic!("Expected prefix expression, got {:?}", statement),
+        }
+    }
+    
+    #[test]
+    fn test_parse_errors() {
+        let tests = [
+            ("let = 5;", TokenType::Assign, vec![TokenType::Ident], "1:5: expected `IDENT`, got `=`"),
+            ("let x 5;", TokenType::Int, vec![TokenType::Assign], "1:7: expected `=`, got `5`"),
+            ("let x = 5", TokenType::Eof, vec![TokenType::Semicolon], "1:10: unexpected end of input, expected `;`"),
+            ("return", TokenType::Eof, vec![], "1:7: unexpected end of input, expected an expression"),
+            ("* 5", TokenType::Asterisk, vec![], "1:1: no prefix parse function for `*`"),
+            ("99999999999999999999", TokenType::Int, vec![], "1:1: invalid integer literal `99999999999999999999`"),
+        ];
+        for (input, token_type, expected, message) in tests {
+            let errors = parse_errors(input);
+            assert_eq!(errors.len(), 1);
+            let error = &errors[0];
+            assert_eq!(error.token().token_type, token_type);
+            assert_eq!(error.expected(), expected.as_slice());
+            assert_eq!(error.to_string(), message);
+        }
+    }
+    
+    #[test]
+    fn test_parse_error_variants() {
+        let errors = parse_errors("let x = ;");
+        assert!(matches!(errors[0], ParseError::MissingPrefixParseFn { .. }));
+        assert_eq!(errors[0].span(), Span::new(8, 9, 1, 9));
+    }
+    
+    #[test]
+    fn test_error_recovery() {
+        let input = r#"
+        let = 1;
+        let x 2;
+        let y = 3;
+        * 4;
+        return 5
+        let z = ;
+        y + 6;
+        "#;
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let (program, errors) = parser.parse_program();
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(messages, vec![
+            "2:13: expected `IDENT`, got `=`",
+            "3:15: expected `=`, got `2`",
+            "5:9: no prefix parse function for `*`",
+            "7:17: no prefix parse function for `;`",
+        ]);
+        assert_eq!(program.show(), "let y = 3;return 5;(y + 6)");
+    }
+    
+    #[test]
+    fn test_boolean_expression() {
+        let program = parse("true; false;");
+        let expected = [true, false];
+        assert_eq!(program.statements.len(), expected.len());
+        for (statement, expected_value) in program.statements.iter().zip(expected) {
+            match statement {
+                Statement::Expression(ExpressionStatement { expression: Expression::Boolean(boolean), .. }) => {
+                    assert_eq!(boolean.value, expected_value);
+                }
+                _ => panic!("Expected boolean expression, got {:?}", statement),
+            }
+        }
+    }
+    
+    #[test]
+    fn test_if_expression() {
+        let program = parse("if (x < y) { x }");
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::If { condition, consequence, alternative, span }, .. }) => {
+                assert_eq!(condition.show(), "(x < y)");
+                assert_eq!(consequence.statements.len(), 1);
+                assert_eq!(consequence.show(), "{ x }");
+                assert_eq!(alternative, &None);
+                assert_eq!(span, &Span::new(0, 16, 1, 1));
+            }
+            statement => panic!("Expected if expression, got {:?}", statement),
+        }
+    }
+    
+    #[test]
+    fn test_if_else_expression() {
+        let program = parse("if (x < y) { x } else { let z = y; z }");
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::If { alternative: Some(alternative), .. }, .. }) => {
+                assert_eq!(alternative.statements.len(), 2);
+            }
+            statement => panic!("Expected if expression, got {:?}", statement),
+        }
+    }
+    
+    #[test]
+    fn test_show_round_trip() {
+        let inputs = [
+            "if (x < y) { x } else { y }",
+            "if (flag) { return 1; }",
+            "if (!ready) { }",
+            "let x = if ((a + b) > c) { let d = a; d } else { false };",
+            "let add = fn(x, y) { x + y; };",
+            "fn() { return f(g(1), 2 * 3); }()",
+            r#"let s = "a \"quoted\"\tword\n" + "\\";"#,
+            r#""\u{1b}[0m""#,
+            "if (a) { x; -y }",
+            "x; -y",
+        ];
+        for input in inputs {
+            let shown = parse(input).show();
+            assert_eq!(parse(&shown).show(), shown, "{}", input);
+        }
+        assert_eq!(parse(inputs[0]).show(), inputs[0]);
+        assert_eq!(parse(inputs[4]).show(), "let add = fn(x, y) { (x + y) };");
+        assert_eq!(parse(inputs[8]).show(), "if (a) { x; (-y) }");
+    }
+    
+    #[test]
+    fn test_block_error_recovery() {
+        let errors = parse_errors("if (x) { let = 1; x } else { * } let y = 2;");
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(messages, vec![
+            "1:14: expected `IDENT`, got `=`",
+            "1:30: no prefix parse function for `*`",
+        ]);
+        let errors = parse_errors("if (x) { x");
+        assert!(matches!(errors[..], [ParseError::UnexpectedEof { .. }]));
+    }
+
+    #[test]
+    fn test_block_error_recovery_keeps_following_statements() {
+        let input = "let f = fn() { let x = };\nlet y = 2;";
+        let mut parser = Parser::new(Lexer::new(input));
+        let (program, errors) = parser.parse_program();
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(messages, vec!["1:24: no prefix parse function for `}`"]);
+        assert_eq!(program.show(), "let f = fn() { };let y = 2;");
+        let errors = parse_errors("} let y = 2;");
+        assert_eq!(errors.len(), 1);
+    }
+    
+    #[test]
+    fn test_function_literal() {
+        let program = parse("fn(x, y) { x + y; }");
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::FunctionLiteral { parameters, body, span }, .. }) => {
+                let names: Vec<&str> = parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+                assert_eq!(names, vec!["x", "y"]);
+                assert_eq!(body.show(), "{ (x + y) }");
+                assert_eq!(span, &Span::new(0, 19, 1, 1));
+            }
+            statement => panic!("Expected function literal, got {:?}", statement),
+        }
+    }
+    
+    #[test]
+    fn test_function_parameters() {
+        let tests = [
+            ("fn() {};", vec![]),
+            ("fn(x) {};", vec!["x"]),
+            ("fn(x, y, z) {};", vec!["x", "y", "z"]),
+        ];
+        for (input, expected) in tests {
+            match &parse(input).statements[0] {
+                Statement::Expression(ExpressionStatement { expression: Expression::FunctionLiteral { parameters, .. }, .. }) => {
+                    let names: Vec<&str> = parameters.iter().map(|parameter| parameter.name.as_str()).collect();
+                    assert_eq!(names, expected);
+                }
+                statement => panic!("Expected function literal, got {:?}", statement),
+            }
+        }
+    }
+    
+    #[test]
+    fn test_call_expression() {
+        let program = parse("add(1, 2 * 3, 4 + 5);");
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::Call { function, arguments, span }, .. }) => {
+                assert_eq!(function.show(), "add");
+                let arguments: Vec<String> = arguments.iter().map(|argument| argument.show()).collect();
+                assert_eq!(arguments, vec!["1", "(2 * 3)", "(4 + 5)"]);
+                assert_eq!(span, &Span::new(0, 20, 1, 1));
+            }
+            statement => panic!("Expected call expression, got {:?}", statement),
+        }
+    }
+    
+    #[test]
+    fn test_array_literal() {
+        let program = parse("[1, 2 * 2, 3 + 3]");
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::Array { elements, span }, .. }) => {
+                let elements: Vec<String> = elements.iter().map(|element| element.show()).collect();
+                assert_eq!(elements, vec!["1", "(2 * 2)", "(3 + 3)"]);
+                assert_eq!(span, &Span::new(0, 17, 1, 1));
+            }
+            statement => panic!("Expected array literal, got {:?}", statement),
+        }
+        assert_eq!(parse("[]").show(), "[]");
+        assert_eq!(parse("[1, 2, 3 + 4]").show(), "[1, 2, (3 + 4)]");
+    }
+
+    #[test]
+    fn test_index_expression() {
+        let program = parse("myArray[1 + 1]");
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::Index { left, index, span }, .. }) => {
+                assert_eq!(left.show(), "myArray");
+                assert_eq!(index.show(), "(1 + 1)");
+                assert_eq!(span, &Span::new(0, 14, 1, 1));
+            }
+            statement => panic!("Expected index expression, got {:?}", statement),
+        }
+        assert_eq!(parse("if (a[0]) { a[1] }").show(), "if (a[0]) { (a[1]) }");
+    }
+
+    #[test]
+    fn test_hash_literal() {
+        let program = parse(r#"{"one": 1, 2: "two", true: 1 + 2}"#);
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::Hash { pairs, span }, .. }) => {
+                let pairs: Vec<(String, String)> = pairs.iter().map(|(key, value)| (key.show(), value.show())).collect();
+                assert_eq!(pairs, vec![
+                    ("\"one\"".to_string(), "1".to_string()),
+                    ("2".to_string(), "\"two\"".to_string()),
+                    ("true".to_string(), "(1 + 2)".to_string()),
+                ]);
+                assert_eq!(span, &Span::new(0, 33, 1, 1));
+            }
+            statement => panic!("Expected hash literal, got {:?}", statement),
+        }
+        assert_eq!(parse("{}").show(), "{}");
+        assert_eq!(parse(r#"let h = {"a": [1], "b": {}}; h["a"]"#).show(), r#"let h = {"a": [1], "b": {}};(h["a"])"#);
+        assert_eq!(parse("if (x) { {1: 2} }").show(), "if (x) { {1: 2} }");
+    }
+
+    #[test]
+    fn test_hash_errors() {
+        let tests = [
+            (r#"{"a" 1}"#, "1:6: expected `:`, got `1`"),
+            (r#"{"a": 1 "b": 2}"#, "1:9: expected one of `,` or `}`, got `b`"),
+            (r#"{"a": 1,}"#, "1:9: no prefix parse function for `}`"),
+            (r#"{"a": 1"#, "1:8: unexpected end of input, expected one of `,` or `}`"),
+        ];
+        for (input, message) in tests {
+            let errors = parse_errors(input);
+            assert_eq!(errors[0].to_string(), message, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_array_and_index_errors() {
+        let tests = [
+            ("[1 2]", "1:4: expected one of `,` or `]`, got `2`"),
+            ("[1, 2", "1:6: unexpected end of input, expected one of `,` or `]`"),
+            ("a[1", "1:4: unexpected end of input, expected `]`"),
+            ("a[]", "1:3: no prefix parse function for `]`"),
+        ];
+        for (input, message) in tests {
+            let errors = parse_errors(input);
+            assert_eq!(errors[0].to_string(), message, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_function_and_call_errors() {
+        let tests = [
+            ("fn(x y) {}", "1:6: expected one of `,` or `)`, got `y`"),
+            ("fn(1) {}", "1:4: expected `IDENT`, got `1`"),
+            ("add(1 2)", "1:7: expected one of `,` or `)`, got `2`"),
+            ("add(1, ", "1:8: unexpected end of input, expected an expression"),
+        ];
+        for (input, message) in tests {
+            let errors = parse_errors(input);
+            assert_eq!(errors[0].to_string(), message, "{}", input);
+        }
+    }
+    
+    #[test]
+    fn test_string_literal() {
+        let program = parse(r#""hello world";"#);
+        match &program.statements[0] {
+            Statement::Expression(ExpressionStatement { expression: Expression::StringLiteral(string), .. }) => {
+                assert_eq!(string.value, "hello world");
+                assert_eq!(string.span, Span::new(0, 13, 1, 1));
+            }
+            statement => panic!("Expected string literal, got {:?}", statement),
+        }
+        assert_eq!(parse(r#""a" + "b\n""#).show(), r#"("a" + "b\n")"#);
+    }
+    
+    #[test]
+    fn test_string_errors() {
+        let tests = [
+            (r#"let s = "abc;"#, "1:9: unterminated string literal"),
+            (r#"let s = "a\qc";"#, r"1:9: invalid escape sequence `\q` in string literal"),
+        ];
+        for (input, message) in tests {
+            let errors = parse_errors(input);
+            assert_eq!(errors.len(), 1, "{}", input);
+            assert_eq!(errors[0].to_string(), message);
+        }
+    }
+    
+    #[test]
+    fn test_doc_comments() {
+        let input = r#"
+        /// Adds two numbers.
+        /// Returns their sum.
+        let add = fn(x, y) { x + y };
+        // not documentation
+        let plain = 1;
+        /// Dropped: documents an expression.
+        plain;
+        let f = fn() {
+            /// Inner.
+            let inner = 2;
+            inner
+        };
+        "#;
+        let lexer = Lexer::new(input).with_doc_comments();
+        let mut parser = Parser::new(lexer);
+        let (program, errors) = parser.parse_program();
+        assert!(errors.is_empty(), "{:?}", errors);
+        let docs: Vec<Option<&str>> = program.statements.iter().filter_map(|statement| match statement {
+            Statement::Let(LetStatement { doc, .. }) => Some(doc.as_deref()),
+            _ => None,
+        }).collect();
+        assert_eq!(docs, vec![Some("Adds two numbers.\nReturns their sum."), None, None]);
+        assert_eq!(program.statements[0].show(), "/// Adds two numbers.\n/// Returns their sum.\nlet add = fn(x, y) { (x + y) };");
+        assert!(program.show().contains("{ /// Inner.\nlet inner = 2; inner }"));
+        assert!(parse(input).statements.iter().all(|statement| !statement.show().contains("///")));
+    }
+    
+    #[test]
+    fn test_unterminated_comment() {
+        let errors = parse_errors("let x = 1; /* open");
+        let messages: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        assert_eq!(messages, vec!["1:12: unterminated block comment"]);
     }
 }
-    
\ No newline at end of file
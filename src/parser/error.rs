@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::lexer::token::{Span, Token, TokenType};
+
+/// Errors own their tokens so they can outlive the source they were parsed from.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseError {
+    UnexpectedToken {
+        token: Token<'static>,
+        expected: Vec<TokenType>,
+    },
+    MissingPrefixParseFn {
+        token: Token<'static>,
+    },
+    InvalidIntegerLiteral {
+        token: Token<'static>,
+    },
+    InvalidFloatLiteral {
+        token: Token<'static>,
+    },
+    UnexpectedEof {
+        token: Token<'static>,
+        expected: Vec<TokenType>,
+    },
+    UnterminatedString {
+        token: Token<'static>,
+    },
+    InvalidEscape {
+        token: Token<'static>,
+    },
+    UnterminatedComment {
+        token: Token<'static>,
+    },
+}
+
+impl ParseError {
+    /// Builds the error for finding `token` where one of `expected` was required, reporting
+    /// running out of input as `UnexpectedEof`.
+    pub fn unexpected(token: &Token, expected: Vec<TokenType>) -> Self {
+        if token.token_type == TokenType::Eof {
+            ParseError::UnexpectedEof { token: token.clone().into_owned(), expected }
+        } else {
+            ParseError::UnexpectedToken { token: token.clone().into_owned(), expected }
+        }
+    }
+
+    pub fn token(&self) -> &Token<'static> {
+        match self {
+            ParseError::UnexpectedToken { token, .. } => token,
+            ParseError::MissingPrefixParseFn { token } => token,
+            ParseError::InvalidIntegerLiteral { token } => token,
+            ParseError::InvalidFloatLiteral { token } => token,
+            ParseError::UnexpectedEof { token, .. } => token,
+            ParseError::UnterminatedString { token } => token,
+            ParseError::InvalidEscape { token } => token,
+            ParseError::UnterminatedComment { token } => token,
+        }
+    }
+
+    pub fn expected(&self) -> &[TokenType] {
+        match self {
+            ParseError::UnexpectedToken { expected, .. } => expected,
+            ParseError::UnexpectedEof { expected, .. } => expected,
+            _ => &[],
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        self.token().span
+    }
+
+    /// Renders the offending line of `source` with a caret under the error's column, followed by
+    /// the message.
+    pub fn render(&self, source: &str) -> String {
+        let span = self.span();
+        let line = source.lines().nth(span.line.saturating_sub(1)).unwrap_or("");
+        format!("  {}\n  {}^ {}", line, " ".repeat(span.column.saturating_sub(1)), self)
+    }
+}
+
+fn describe(token: &Token) -> String {
+    if token.literal.is_empty() {
+        token.token_type.to_string()
+    } else {
+        format!("`{}`", token.literal)
+    }
+}
+
+fn describe_expected(expected: &[TokenType]) -> String {
+    let names: Vec<String> = expected.iter().map(|token_type| format!("`{}`", token_type)).collect();
+    match names.split_last() {
+        None => "an expression".to_string(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("one of {} or {}", rest.join(", "), last),
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedToken { token, expected } => {
+                write!(f, "{}: expected {}, got {}", token.span, describe_expected(expected), describe(token))
+            }
+            ParseError::MissingPrefixParseFn { token } => {
+                write!(f, "{}: no prefix parse function for {}", token.span, describe(token))
+            }
+            ParseError::InvalidIntegerLiteral { token } => {
+                write!(f, "{}: invalid integer literal {}", token.span, describe(token))
+            }
+            ParseError::InvalidFloatLiteral { token } => {
+                write!(f, "{}: invalid float literal {}", token.span, describe(token))
+            }
+            ParseError::UnexpectedEof { token, expected } => {
+                write!(f, "{}: unexpected end of input, expected {}", token.span, describe_expected(expected))
+            }
+            ParseError::UnterminatedString { token } => {
+                write!(f, "{}: unterminated string literal", token.span)
+            }
+            ParseError::InvalidEscape { token } => {
+                write!(f, "{}: invalid escape sequence {} in string literal", token.span, describe(token))
+            }
+            ParseError::UnterminatedComment { token } => {
+                write!(f, "{}: unterminated block comment", token.span)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -1,16 +1,38 @@
 use crate::ast::Expression;
+use crate::lexer::token::TokenType;
+use crate::parser::error::ParseError;
 use crate::parser::Parser;
 
-pub type PrefixParseFn = fn(&mut Parser) -> Result<Expression, String>;
-pub type InfixParseFn = fn(&mut Parser, Expression) -> Result<Expression, String>;
+pub type PrefixParseFn = fn(&mut Parser) -> Result<Expression, ParseError>;
+pub type InfixParseFn = fn(&mut Parser, Expression) -> Result<Expression, ParseError>;
 
 #[derive(Debug, PartialEq, Clone, PartialOrd)]
 pub enum Precedence {
     Lowest,
+    LogicalOr,
+    LogicalAnd,
     Equals,
     LessGreater,
     Sum,
     Product,
     Prefix,
     Call,
-}
\ No newline at end of file
+    Index,
+}
+
+impl From<&TokenType> for Precedence {
+    fn from(token_type: &TokenType) -> Self {
+        match token_type {
+            TokenType::Or => Precedence::LogicalOr,
+            TokenType::And => Precedence::LogicalAnd,
+            TokenType::Equals | TokenType::NotEqual => Precedence::Equals,
+            TokenType::LesserThan | TokenType::GreaterThan => Precedence::LessGreater,
+            TokenType::LesserEqual | TokenType::GreaterEqual => Precedence::LessGreater,
+            TokenType::Plus | TokenType::Dash => Precedence::Sum,
+            TokenType::Asterisk | TokenType::ForwardSlash | TokenType::Percent => Precedence::Product,
+            TokenType::Lparen => Precedence::Call,
+            TokenType::Lbracket => Precedence::Index,
+            _ => Precedence::Lowest,
+        }
+    }
+}
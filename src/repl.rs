@@ -1,24 +1,253 @@
 use std::io;
-use std::io::Write;
+use std::io::{BufRead, Write};
+use crate::ast::show::Show;
+use crate::ast::Statement;
+use crate::eval::environment::{Environment, OutputBuffer};
+use crate::eval::eval_program;
 use crate::lexer::Lexer;
+use crate::parser::error::ParseError;
+use crate::parser::Parser;
 
-const PROMPT: &str = ">> ";
-pub struct Repl {}
-impl Repl {
-    pub fn new() -> Self {
-        Self {}
+pub const DEFAULT_PROMPT: &str = ">> ";
+pub const DEFAULT_CONTINUATION_PROMPT: &str = ".. ";
+const TOKENS_COMMAND: &str = ":tokens";
+const AST_COMMAND: &str = ":ast";
+const QUIT_COMMAND: &str = ":quit";
+
+/// Reads lines from `input` and evaluates them against an environment that persists for the
+/// whole session, writing prompts and results to `output`. `:tokens <input>` dumps the tokens
+/// of `<input>` and `:ast <input>` shows the parsed program instead of evaluating it. The
+/// session ends at end of input or on `:quit`.
+///
+/// Input that stops in the middle of a construct, such as `let add = fn(x, y) {`, is continued
+/// on the following lines under the continuation prompt. A blank line submits it as it is.
+///
+/// What the program itself prints, such as with `puts`, also goes to `output`, ahead of the
+/// result it belongs to.
+pub struct Repl<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    prompt: String,
+    continuation_prompt: String,
+    env: Environment,
+    program_output: OutputBuffer,
+}
+
+impl<R: BufRead, W: Write> Repl<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        let program_output = OutputBuffer::default();
+        Self {
+            input,
+            output,
+            prompt: DEFAULT_PROMPT.to_string(),
+            continuation_prompt: DEFAULT_CONTINUATION_PROMPT.to_string(),
+            env: Environment::with_output(program_output.clone()),
+            program_output,
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    pub fn with_continuation_prompt(mut self, continuation_prompt: impl Into<String>) -> Self {
+        self.continuation_prompt = continuation_prompt.into();
+        self
     }
 
-    pub fn start(&self) {
+    /// Runs the session until end of input or `:quit`. Unreadable lines are reported and
+    /// skipped; other I/O errors end the session and are returned.
+    pub fn start(&mut self) -> io::Result<()> {
+        let mut pending = String::new();
         loop {
             let mut input = String::new();
-            print!("{}", PROMPT);
-            io::stdout().flush().unwrap();
-            io::stdin().read_line(&mut input).unwrap();
-            let lexer = Lexer::new(input);
-            for token in lexer {
-                println!("{:?}", token);
+            let prompt = if pending.is_empty() { &self.prompt } else { &self.continuation_prompt };
+            write!(self.output, "{}", prompt)?;
+            self.output.flush()?;
+            match self.input.read_line(&mut input) {
+                Ok(0) => {
+                    if !pending.is_empty() {
+                        self.eval(&pending)?;
+                    }
+                    return writeln!(self.output);
+                }
+                Ok(_) => {}
+                Err(error) => match error.kind() {
+                    io::ErrorKind::Interrupted | io::ErrorKind::InvalidData => {
+                        writeln!(self.output, "error reading input: {}", error)?;
+                        continue;
+                    }
+                    _ => return Err(error),
+                },
             }
+            let input = input.trim_end();
+            if input == QUIT_COMMAND {
+                return Ok(());
+            }
+            if !pending.is_empty() {
+                if input.is_empty() {
+                    self.eval(&std::mem::take(&mut pending))?;
+                    continue;
+                }
+                pending.push('\n');
+            } else if let Some(source) = input.strip_prefix(TOKENS_COMMAND) {
+                self.print_tokens(source.trim_start())?;
+                continue;
+            } else if let Some(source) = input.strip_prefix(AST_COMMAND) {
+                self.print_ast(source.trim_start())?;
+                continue;
+            }
+            pending.push_str(input);
+            if !is_incomplete(&pending) {
+                self.eval(&std::mem::take(&mut pending))?;
+            }
+        }
+    }
+
+    fn print_tokens(&mut self, source: &str) -> io::Result<()> {
+        let lexer = Lexer::new(source);
+        for token in lexer {
+            writeln!(self.output, "{:?}", token)?;
         }
+        Ok(())
+    }
+
+    fn print_ast(&mut self, source: &str) -> io::Result<()> {
+        let mut parser = Parser::new(Lexer::new(source));
+        let (program, errors) = parser.parse_program();
+        if !errors.is_empty() {
+            return self.print_parse_errors(source, &errors);
+        }
+        writeln!(self.output, "{}", program.show())
+    }
+
+    fn eval(&mut self, source: &str) -> io::Result<()> {
+        let mut parser = Parser::new(Lexer::new(source));
+        let (program, errors) = parser.parse_program();
+        if !errors.is_empty() {
+            return self.print_parse_errors(source, &errors);
+        }
+        let result = eval_program(&program, &mut self.env);
+        self.output.write_all(&self.program_output.take())?;
+        // A trailing `let` has nothing worth showing unless it failed.
+        if result.is_error() || !matches!(program.statements.last(), None | Some(Statement::Let(_))) {
+            writeln!(self.output, "{}", result)?;
+        }
+        Ok(())
+    }
+
+    fn print_parse_errors(&mut self, source: &str, errors: &[ParseError]) -> io::Result<()> {
+        for error in errors {
+            writeln!(self.output, "{}", error.render(source))?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the parser ran out of input part-way through a construct. This covers unclosed
+/// braces, parentheses, strings and block comments, trailing operators and statements still
+/// missing their `;`. Only the first error counts: once the input has a real error, running out
+/// of input later on is a consequence of it and more lines will not fix it.
+fn is_incomplete(source: &str) -> bool {
+    let mut parser = Parser::new(Lexer::new(source));
+    let (_, errors) = parser.parse_program();
+    matches!(
+        errors.first(),
+        Some(ParseError::UnexpectedEof { .. } | ParseError::UnterminatedString { .. } | ParseError::UnterminatedComment { .. })
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::Repl;
+
+    fn run(input: &str) -> String {
+        let mut output = Vec::new();
+        Repl::new(input.as_bytes(), &mut output)
+            .with_prompt("")
+            .with_continuation_prompt("")
+            .start()
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_bindings_persist_across_lines() {
+        let transcript = run("let add = fn(x, y) { x + y };\nlet a = 2;\nadd(a, 3)\n");
+        assert_eq!(transcript, "5\n\n");
+    }
+
+    #[test]
+    fn test_program_output() {
+        let transcript = run("puts(1, \"two\")\nlet x = puts(3);\nlet f = fn() { puts(\"in f\"); 4 };\nf()\n");
+        assert_eq!(transcript, "1\ntwo\nnull\n3\nin f\n4\n\n");
+    }
+
+    #[test]
+    fn test_prompt() {
+        let mut output = Vec::new();
+        Repl::new("1\n".as_bytes(), &mut output).with_prompt("monkey> ").start().unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "monkey> 1\nmonkey> \n");
+    }
+
+    #[test]
+    fn test_errors() {
+        let transcript = run("let = 5;\nfoo\n");
+        assert_eq!(transcript, "  let = 5;\n      ^ 1:5: expected `IDENT`, got `=`\nERROR: identifier not found: foo\n\n");
+    }
+
+    #[test]
+    fn test_meta_commands() {
+        let transcript = run(":ast 1 + 2 * 3\n:tokens !x\n:quit\n4\n");
+        assert_eq!(
+            transcript,
+            "(1 + (2 * 3))\n\
+             Token { token_type: Bang, literal: \"!\", span: Span { start: 0, end: 1, line: 1, column: 1 } }\n\
+             Token { token_type: Ident, literal: \"x\", span: Span { start: 1, end: 2, line: 1, column: 2 } }\n"
+        );
+    }
+
+    #[test]
+    fn test_multi_line_input() {
+        let mut output = Vec::new();
+        let input = "let add = fn(x, y) {\n  x +\n  y\n};\nadd(1,\n2)\n";
+        Repl::new(input.as_bytes(), &mut output).start().unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), ">> .. .. .. >> .. 3\n>> \n");
+    }
+
+    #[test]
+    fn test_blank_line_submits_incomplete_input() {
+        let transcript = run("let x = 5\n\nif (x) {\n");
+        assert_eq!(
+            transcript,
+            "  let x = 5\n           ^ 1:10: unexpected end of input, expected `;`\n\
+             \x20 if (x) {\n          ^ 1:9: unexpected end of input, expected `}`\n\n"
+        );
+    }
+
+    #[test]
+    fn test_error_before_end_of_input_is_not_continued() {
+        let transcript = run("if (true) { 1 + }\n2\n");
+        assert_eq!(transcript, "  if (true) { 1 + }\n                  ^ 1:17: no prefix parse function for `}`\n2\n\n");
+    }
+
+    #[test]
+    fn test_quit_at_continuation_prompt() {
+        let transcript = run("let x = fn() {\n:quit\n1\n");
+        assert_eq!(transcript, "");
+    }
+
+    #[test]
+    fn test_multi_line_string() {
+        let transcript = run("let s = \"one\ntwo\";\ns\n");
+        assert_eq!(transcript, "one\ntwo\n\n");
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_skipped() {
+        let mut output = Vec::new();
+        Repl::new(&b"1\n\xff\n2\n"[..], &mut output).with_prompt("").start().unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "1\nerror reading input: stream did not contain valid UTF-8\n2\n\n");
     }
-}
\ No newline at end of file
+}